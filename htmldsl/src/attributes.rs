@@ -1,9 +1,10 @@
 use super::units;
-use htmldsl_internal::attr_traits::Attribute;
+use htmldsl_internal::attr_traits::{Attribute, AttributeSet};
+use htmldsl_internal::element_traits::TagRenderableGlobalAttrs;
 use htmldsl_internal::style_traits;
 
 pub struct StyleAttr<'a> {
-    pub values: Vec<&'a dyn style_traits::Style>,
+    pub values: Vec<Box<dyn style_traits::Style + 'a>>,
 }
 
 impl<'a> StyleAttr<'a> {
@@ -11,7 +12,7 @@ impl<'a> StyleAttr<'a> {
         StyleAttr { values: Vec::new() }
     }
 
-    pub fn new(styles: Vec<&'a dyn style_traits::Style>) -> Self {
+    pub fn new(styles: Vec<Box<dyn style_traits::Style + 'a>>) -> Self {
         StyleAttr { values: styles }
     }
 }
@@ -26,8 +27,198 @@ impl<'a> Attribute for StyleAttr<'a> {
     }
 }
 
+/// The attributes which are allowed on every element, rendered in field order.
+pub struct GlobalAttrs {
+    pub id: Option<Id>,
+    pub class: Option<Class>,
+    pub title: Option<Title>,
+    pub hidden: Option<Hidden>,
+    pub tabindex: Option<Tabindex>,
+    pub dir: Option<Dir>,
+    pub lang: Option<Lang>,
+    pub draggable: Option<Draggable>,
+    pub contenteditable: Option<Contenteditable>,
+    pub translate: Option<Translate>,
+    pub role: Option<Role>,
+    pub aria: AriaAttrs,
+    pub data: DataAttrs,
+}
+
+impl GlobalAttrs {
+    pub fn empty() -> Self {
+        GlobalAttrs {
+            id: None,
+            class: None,
+            title: None,
+            hidden: None,
+            tabindex: None,
+            dir: None,
+            lang: None,
+            draggable: None,
+            contenteditable: None,
+            translate: None,
+            role: None,
+            aria: AriaAttrs::empty(),
+            data: DataAttrs::empty(),
+        }
+    }
+}
+
+impl AttributeSet for GlobalAttrs {
+    fn attributes(&self) -> Vec<&dyn Attribute> {
+        let mut attrs: Vec<&dyn Attribute> = Vec::new();
+        if let Some(ref a) = self.id {
+            attrs.push(a);
+        }
+        if let Some(ref a) = self.class {
+            if !a.is_empty() {
+                attrs.push(a);
+            }
+        }
+        if let Some(ref a) = self.title {
+            attrs.push(a);
+        }
+        if let Some(ref a) = self.hidden {
+            attrs.push(a);
+        }
+        if let Some(ref a) = self.tabindex {
+            attrs.push(a);
+        }
+        if let Some(ref a) = self.dir {
+            attrs.push(a);
+        }
+        if let Some(ref a) = self.lang {
+            attrs.push(a);
+        }
+        if let Some(ref a) = self.draggable {
+            attrs.push(a);
+        }
+        if let Some(ref a) = self.contenteditable {
+            attrs.push(a);
+        }
+        if let Some(ref a) = self.translate {
+            attrs.push(a);
+        }
+        if let Some(ref a) = self.role {
+            attrs.push(a);
+        }
+        attrs.extend(self.aria.attributes());
+        attrs.extend(self.data.attributes());
+        attrs
+    }
+}
+
+/// Setters for the global attributes, in the style of `add_style`. Implemented for every element
+/// deriving `TagRenderableGlobalAttrsSetter`.
+pub trait TagRenderableGlobalAttrsSetter:
+    TagRenderableGlobalAttrs<GlobalAttrs = GlobalAttrs> + Sized
+{
+    fn with_global_attrs<F: FnOnce(&mut GlobalAttrs)>(mut self, f: F) -> Self {
+        f(self.global_attrs_mut());
+        self
+    }
+
+    fn with_id<S: Into<String>>(mut self, name: S) -> Self {
+        self.global_attrs_mut().id = Some(Id { name: name.into() });
+        self
+    }
+
+    fn with_class(mut self, class: Class) -> Self {
+        self.global_attrs_mut().class = Some(class);
+        self
+    }
+
+    fn add_class<S: Into<String>>(mut self, name: S) -> Result<Self, String> {
+        let attrs = self.global_attrs_mut();
+        let mut class = attrs.class.take().unwrap_or_else(Class::empty);
+        let result = class.insert(name.into());
+        attrs.class = Some(class);
+        result.map(|_| self)
+    }
+
+    fn add_class_if<S: Into<String>>(self, cond: bool, name: S) -> Result<Self, String> {
+        match cond {
+            true => self.add_class(name),
+            false => Ok(self),
+        }
+    }
+
+    fn with_title<S: Into<String>>(mut self, title: S) -> Self {
+        self.global_attrs_mut().title = Some(Title {
+            inner: units::ValueString::new(title.into()),
+        });
+        self
+    }
+
+    fn with_hidden(mut self) -> Self {
+        self.global_attrs_mut().hidden = Some(Hidden {});
+        self
+    }
+
+    fn with_tabindex(mut self, value: i32) -> Self {
+        self.global_attrs_mut().tabindex = Some(Tabindex { value });
+        self
+    }
+
+    fn with_dir(mut self, value: units::DirValue) -> Self {
+        self.global_attrs_mut().dir = Some(Dir { value });
+        self
+    }
+
+    fn with_lang(mut self, lang: Lang) -> Self {
+        self.global_attrs_mut().lang = Some(lang);
+        self
+    }
+
+    /// Sets `lang` along with the `dir` its script is written in.
+    fn with_lang_and_dir(mut self, lang: Lang) -> Self {
+        let attrs = self.global_attrs_mut();
+        attrs.dir = Some(lang.dir());
+        attrs.lang = Some(lang);
+        self
+    }
+
+    fn with_draggable(mut self, value: bool) -> Self {
+        self.global_attrs_mut().draggable = Some(Draggable { value });
+        self
+    }
+
+    fn with_contenteditable(mut self, value: units::ContenteditableValue) -> Self {
+        self.global_attrs_mut().contenteditable = Some(Contenteditable { value });
+        self
+    }
+
+    fn with_translate(mut self, value: bool) -> Self {
+        self.global_attrs_mut().translate = Some(Translate { value });
+        self
+    }
+
+    fn with_role(mut self, value: units::RoleValue) -> Self {
+        self.global_attrs_mut().role = Some(Role { value });
+        self
+    }
+
+    fn add_aria(mut self, aria: AriaAttr) -> Self {
+        self.global_attrs_mut().aria.insert(aria);
+        self
+    }
+
+    fn add_data<K: Into<String>, V: Into<String>>(
+        mut self,
+        key: K,
+        value: V,
+    ) -> Result<Self, String> {
+        self.global_attrs_mut()
+            .data
+            .insert(key.into(), value.into())?;
+        Ok(self)
+    }
+}
+
+impl<T: TagRenderableGlobalAttrs<GlobalAttrs = GlobalAttrs>> TagRenderableGlobalAttrsSetter for T {}
+
 pub struct Id {
-    pub name: &'static str,
+    pub name: String,
 }
 
 impl Attribute for Id {
@@ -36,13 +227,497 @@ impl Attribute for Id {
     }
 
     fn attr_value(&self) -> String {
-        self.name.to_string()
+        self.name.clone()
+    }
+}
+
+/// An ordered set of class names, a name added twice is only rendered once. A name may not be
+/// empty or hold any ascii whitespace, which the browser would split it on.
+pub struct Class {
+    names: Vec<String>,
+}
+
+impl Class {
+    pub fn empty() -> Self {
+        Class { names: Vec::new() }
+    }
+
+    pub fn new<S: Into<String>>(name: S) -> Result<Self, String> {
+        Class::empty().with(name)
+    }
+
+    pub fn with<S: Into<String>>(mut self, name: S) -> Result<Self, String> {
+        self.insert(name.into())?;
+        Ok(self)
+    }
+
+    pub fn with_if<S: Into<String>>(self, cond: bool, name: S) -> Result<Self, String> {
+        match cond {
+            true => self.with(name),
+            false => Ok(self),
+        }
+    }
+
+    pub fn extend<S: Into<String>, I: IntoIterator<Item = S>>(
+        mut self,
+        names: I,
+    ) -> Result<Self, String> {
+        for name in names {
+            self.insert(name.into())?;
+        }
+        Ok(self)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.iter().any(|n| n == name)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(|n| n.as_str())
+    }
+
+    fn insert(&mut self, name: String) -> Result<(), String> {
+        if name.is_empty() {
+            return Err("a class name can not be empty".into());
+        }
+        if name.contains(|c: char| c.is_ascii_whitespace()) {
+            return Err(format!("class name {:?} holds whitespace", name));
+        }
+        if !self.contains(&name) {
+            self.names.push(name);
+        }
+        Ok(())
+    }
+}
+
+impl Attribute for Class {
+    fn attr_key(&self) -> String {
+        "class".into()
+    }
+
+    fn attr_value(&self) -> String {
+        self.names.join(" ")
+    }
+}
+
+pub struct Title {
+    pub inner: units::ValueString,
+}
+
+impl Attribute for Title {
+    fn attr_key(&self) -> String {
+        "title".into()
+    }
+
+    fn attr_value(&self) -> String {
+        self.inner.value_string()
+    }
+}
+
+/// A boolean attribute, present means hidden.
+pub struct Hidden {}
+
+impl Attribute for Hidden {
+    fn attr_key(&self) -> String {
+        "hidden".into()
+    }
+
+    fn attr_value(&self) -> String {
+        "".into()
+    }
+
+    fn is_boolean(&self) -> bool {
+        true
+    }
+}
+
+pub struct Tabindex {
+    pub value: i32,
+}
+
+impl Attribute for Tabindex {
+    fn attr_key(&self) -> String {
+        "tabindex".into()
+    }
+
+    fn attr_value(&self) -> String {
+        self.value.to_string()
+    }
+}
+
+pub struct Dir {
+    pub value: units::DirValue,
+}
+
+impl Attribute for Dir {
+    fn attr_key(&self) -> String {
+        "dir".into()
+    }
+
+    fn attr_value(&self) -> String {
+        self.value.value_string()
+    }
+}
+
+pub struct Draggable {
+    pub value: bool,
+}
+
+impl Attribute for Draggable {
+    fn attr_key(&self) -> String {
+        "draggable".into()
+    }
+
+    fn attr_value(&self) -> String {
+        self.value.to_string()
+    }
+}
+
+pub struct Contenteditable {
+    pub value: units::ContenteditableValue,
+}
+
+impl Attribute for Contenteditable {
+    fn attr_key(&self) -> String {
+        "contenteditable".into()
+    }
+
+    fn attr_value(&self) -> String {
+        self.value.value_string()
+    }
+}
+
+pub struct Translate {
+    pub value: bool,
+}
+
+impl Attribute for Translate {
+    fn attr_key(&self) -> String {
+        "translate".into()
+    }
+
+    fn attr_value(&self) -> String {
+        match self.value {
+            true => "yes",
+            false => "no",
+        }
+        .into()
+    }
+}
+
+pub struct Role {
+    pub value: units::RoleValue,
+}
+
+impl Attribute for Role {
+    fn attr_key(&self) -> String {
+        "role".into()
+    }
+
+    fn attr_value(&self) -> String {
+        self.value.value_string()
+    }
+}
+
+/// A typed `aria-*` state or property.
+pub enum AriaAttr {
+    Atomic(bool),
+    Busy(bool),
+    Disabled(bool),
+    Expanded(bool),
+    Hidden(bool),
+    Modal(bool),
+    Multiline(bool),
+    Multiselectable(bool),
+    Readonly(bool),
+    Required(bool),
+    Selected(bool),
+    Checked(units::Tristate),
+    Pressed(units::Tristate),
+    Activedescendant(units::IdRef),
+    Errormessage(units::IdRef),
+    Controls(Vec<units::IdRef>),
+    Describedby(Vec<units::IdRef>),
+    Details(Vec<units::IdRef>),
+    Flowto(Vec<units::IdRef>),
+    Labelledby(Vec<units::IdRef>),
+    Owns(Vec<units::IdRef>),
+    Label(String),
+    Description(String),
+    Placeholder(String),
+    Roledescription(String),
+    Keyshortcuts(String),
+    Valuetext(String),
+    Level(std::num::NonZeroU32),
+    Posinset(std::num::NonZeroU32),
+    Setsize(units::AriaSizeValue),
+    Colcount(units::AriaSizeValue),
+    Colindex(std::num::NonZeroU32),
+    Colspan(std::num::NonZeroU32),
+    Rowcount(units::AriaSizeValue),
+    Rowindex(std::num::NonZeroU32),
+    /// Zero spans the rest of the row group
+    Rowspan(u32),
+    Valuemax(units::Finite),
+    Valuemin(units::Finite),
+    Valuenow(units::Finite),
+    Live(units::AriaLiveValue),
+    Current(units::AriaCurrentValue),
+    Haspopup(units::AriaHaspopupValue),
+    Invalid(units::AriaInvalidValue),
+    Orientation(units::AriaOrientationValue),
+    Sort(units::AriaSortValue),
+    Autocomplete(units::AriaAutocompleteValue),
+}
+
+impl AriaAttr {
+    fn name(&self) -> &'static str {
+        match self {
+            AriaAttr::Atomic(_) => "atomic",
+            AriaAttr::Busy(_) => "busy",
+            AriaAttr::Disabled(_) => "disabled",
+            AriaAttr::Expanded(_) => "expanded",
+            AriaAttr::Hidden(_) => "hidden",
+            AriaAttr::Modal(_) => "modal",
+            AriaAttr::Multiline(_) => "multiline",
+            AriaAttr::Multiselectable(_) => "multiselectable",
+            AriaAttr::Readonly(_) => "readonly",
+            AriaAttr::Required(_) => "required",
+            AriaAttr::Selected(_) => "selected",
+            AriaAttr::Checked(_) => "checked",
+            AriaAttr::Pressed(_) => "pressed",
+            AriaAttr::Activedescendant(_) => "activedescendant",
+            AriaAttr::Errormessage(_) => "errormessage",
+            AriaAttr::Controls(_) => "controls",
+            AriaAttr::Describedby(_) => "describedby",
+            AriaAttr::Details(_) => "details",
+            AriaAttr::Flowto(_) => "flowto",
+            AriaAttr::Labelledby(_) => "labelledby",
+            AriaAttr::Owns(_) => "owns",
+            AriaAttr::Label(_) => "label",
+            AriaAttr::Description(_) => "description",
+            AriaAttr::Placeholder(_) => "placeholder",
+            AriaAttr::Roledescription(_) => "roledescription",
+            AriaAttr::Keyshortcuts(_) => "keyshortcuts",
+            AriaAttr::Valuetext(_) => "valuetext",
+            AriaAttr::Level(_) => "level",
+            AriaAttr::Posinset(_) => "posinset",
+            AriaAttr::Setsize(_) => "setsize",
+            AriaAttr::Colcount(_) => "colcount",
+            AriaAttr::Colindex(_) => "colindex",
+            AriaAttr::Colspan(_) => "colspan",
+            AriaAttr::Rowcount(_) => "rowcount",
+            AriaAttr::Rowindex(_) => "rowindex",
+            AriaAttr::Rowspan(_) => "rowspan",
+            AriaAttr::Valuemax(_) => "valuemax",
+            AriaAttr::Valuemin(_) => "valuemin",
+            AriaAttr::Valuenow(_) => "valuenow",
+            AriaAttr::Live(_) => "live",
+            AriaAttr::Current(_) => "current",
+            AriaAttr::Haspopup(_) => "haspopup",
+            AriaAttr::Invalid(_) => "invalid",
+            AriaAttr::Orientation(_) => "orientation",
+            AriaAttr::Sort(_) => "sort",
+            AriaAttr::Autocomplete(_) => "autocomplete",
+        }
+    }
+}
+
+impl Attribute for AriaAttr {
+    fn attr_key(&self) -> String {
+        format!("aria-{}", self.name())
+    }
+
+    fn attr_value(&self) -> String {
+        match self {
+            AriaAttr::Atomic(v)
+            | AriaAttr::Busy(v)
+            | AriaAttr::Disabled(v)
+            | AriaAttr::Expanded(v)
+            | AriaAttr::Hidden(v)
+            | AriaAttr::Modal(v)
+            | AriaAttr::Multiline(v)
+            | AriaAttr::Multiselectable(v)
+            | AriaAttr::Readonly(v)
+            | AriaAttr::Required(v)
+            | AriaAttr::Selected(v) => v.to_string(),
+            AriaAttr::Checked(v) | AriaAttr::Pressed(v) => v.value_string(),
+            AriaAttr::Activedescendant(v) | AriaAttr::Errormessage(v) => v.value_string(),
+            AriaAttr::Controls(v)
+            | AriaAttr::Describedby(v)
+            | AriaAttr::Details(v)
+            | AriaAttr::Flowto(v)
+            | AriaAttr::Labelledby(v)
+            | AriaAttr::Owns(v) => v
+                .iter()
+                .map(|v| v.value_string())
+                .collect::<Vec<String>>()
+                .join(" "),
+            AriaAttr::Label(v)
+            | AriaAttr::Description(v)
+            | AriaAttr::Placeholder(v)
+            | AriaAttr::Roledescription(v)
+            | AriaAttr::Keyshortcuts(v)
+            | AriaAttr::Valuetext(v) => v.clone(),
+            AriaAttr::Level(v)
+            | AriaAttr::Posinset(v)
+            | AriaAttr::Colindex(v)
+            | AriaAttr::Colspan(v)
+            | AriaAttr::Rowindex(v) => v.to_string(),
+            AriaAttr::Setsize(v) | AriaAttr::Colcount(v) | AriaAttr::Rowcount(v) => {
+                v.value_string()
+            }
+            AriaAttr::Rowspan(v) => v.to_string(),
+            AriaAttr::Valuemax(v) | AriaAttr::Valuemin(v) | AriaAttr::Valuenow(v) => {
+                v.value_string()
+            }
+            AriaAttr::Live(v) => v.value_string(),
+            AriaAttr::Current(v) => v.value_string(),
+            AriaAttr::Haspopup(v) => v.value_string(),
+            AriaAttr::Invalid(v) => v.value_string(),
+            AriaAttr::Orientation(v) => v.value_string(),
+            AriaAttr::Sort(v) => v.value_string(),
+            AriaAttr::Autocomplete(v) => v.value_string(),
+        }
+    }
+}
+
+/// The `aria-*` attributes of an element, setting a property twice keeps the last value.
+pub struct AriaAttrs {
+    attrs: Vec<AriaAttr>,
+}
+
+impl AriaAttrs {
+    pub fn empty() -> Self {
+        AriaAttrs { attrs: Vec::new() }
+    }
+
+    pub fn with(mut self, aria: AriaAttr) -> Self {
+        self.insert(aria);
+        self
+    }
+
+    pub fn insert(&mut self, aria: AriaAttr) {
+        match self.attrs.iter().position(|a| a.name() == aria.name()) {
+            Some(i) => self.attrs[i] = aria,
+            None => self.attrs.push(aria),
+        }
+    }
+}
+
+impl AttributeSet for AriaAttrs {
+    fn attributes(&self) -> Vec<&dyn Attribute> {
+        self.attrs.iter().map(|a| a as &dyn Attribute).collect()
+    }
+}
+
+/// A single custom `data-*` attribute, the key is given without the `data-` prefix.
+pub struct DataAttr {
+    key: String,
+    value: String,
+}
+
+impl DataAttr {
+    /// The key must be xml compatible, without uppercase ascii and not starting with "xml".
+    pub fn new<K: Into<String>, V: Into<String>>(key: K, value: V) -> Result<Self, String> {
+        let key = key.into();
+        if key.is_empty() {
+            return Err("data attribute key must not be empty".into());
+        }
+        if key.to_ascii_lowercase().starts_with("xml") {
+            return Err(format!(
+                "data attribute key {:?} must not start with \"xml\"",
+                key
+            ));
+        }
+        if let Some(c) = key.chars().find(|c| {
+            !(c.is_ascii_lowercase()
+                || c.is_ascii_digit()
+                || *c == '-'
+                || *c == '_'
+                || *c == '.'
+                || (!c.is_ascii() && !c.is_whitespace()))
+        }) {
+            return Err(format!(
+                "data attribute key {:?} must not contain {:?}",
+                key, c
+            ));
+        }
+        Ok(DataAttr {
+            key,
+            value: value.into(),
+        })
+    }
+}
+
+impl Attribute for DataAttr {
+    fn attr_key(&self) -> String {
+        format!("data-{}", self.key)
+    }
+
+    fn attr_value(&self) -> String {
+        self.value.clone()
+    }
+}
+
+/// The `data-*` attributes of an element, in insertion order, setting a key twice keeps the
+/// last value.
+pub struct DataAttrs {
+    attrs: Vec<DataAttr>,
+}
+
+impl DataAttrs {
+    pub fn empty() -> Self {
+        DataAttrs { attrs: Vec::new() }
+    }
+
+    pub fn with<K: Into<String>, V: Into<String>>(
+        mut self,
+        key: K,
+        value: V,
+    ) -> Result<Self, String> {
+        self.insert(key.into(), value.into())?;
+        Ok(self)
+    }
+
+    pub fn insert(&mut self, key: String, value: String) -> Result<(), String> {
+        let data = DataAttr::new(key, value)?;
+        match self.attrs.iter_mut().find(|a| a.key == data.key) {
+            Some(a) => a.value = data.value,
+            None => self.attrs.push(data),
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|a| a.key == key)
+            .map(|a| a.value.as_str())
+    }
+}
+
+impl AttributeSet for DataAttrs {
+    fn attributes(&self) -> Vec<&dyn Attribute> {
+        self.attrs.iter().map(|a| a as &dyn Attribute).collect()
     }
 }
 
 pub struct Lang {
     pub tag: units::LanguageTag,
-    pub sub_tag: units::LanguageSubTag,
+}
+
+impl Lang {
+    /// The `dir` attribute matching the tag's script.
+    pub fn dir(&self) -> Dir {
+        Dir {
+            value: self.tag.direction(),
+        }
+    }
 }
 
 impl Attribute for Lang {
@@ -51,7 +726,7 @@ impl Attribute for Lang {
     }
 
     fn attr_value(&self) -> String {
-        format!("{}-{}", self.tag.lang_tag_str(), self.sub_tag.sub_tag_str())
+        self.tag.lang_tag_str()
     }
 }
 
@@ -166,3 +841,112 @@ impl Attribute for Name {
         self.inner.value_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements;
+    use htmldsl_internal::element_traits::{Renderable, TagRenderableIntoElement};
+
+    #[test]
+    fn class_names_are_deduplicated() {
+        let class = Class::new("btn")
+            .and_then(|c| c.with("btn-primary"))
+            .and_then(|c| c.with("btn"))
+            .and_then(|c| c.with_if(true, "active"))
+            .and_then(|c| c.with_if(false, "hidden"))
+            .and_then(|c| c.extend(vec!["a", "btn-primary"]))
+            .unwrap();
+        assert_eq!(
+            class.names().collect::<Vec<_>>(),
+            vec!["btn", "btn-primary", "active", "a"]
+        );
+        assert_eq!(class.attr_value(), "btn btn-primary active a");
+    }
+
+    #[test]
+    fn class_names_can_not_hold_whitespace() {
+        assert!(Class::new("").is_err());
+        assert!(Class::new("btn btn-primary").is_err());
+        assert!(Class::new("btn\t").is_err());
+        assert!(Class::empty().extend(vec!["a", "b\n"]).is_err());
+        assert!(Class::empty().with_if(false, "a b").is_ok());
+        // Only ascii whitespace separates class names
+        assert!(Class::new("a\u{a0}b").unwrap().contains("a\u{a0}b"));
+
+        let span = elements::Span::style_less(vec![])
+            .add_class("a")
+            .and_then(|s| s.add_class_if(true, "b"))
+            .unwrap();
+        assert_eq!(
+            Renderable::Tag(&span).try_render().unwrap(),
+            "<span class=\"a b\"></span>"
+        );
+        assert!(elements::Span::style_less(vec![]).add_class("a b").is_err());
+    }
+
+    #[test]
+    fn global_attrs_take_runtime_strings() {
+        let id = format!("item-{}", 7);
+        let span = elements::Span::style_less(vec![])
+            .with_id(id)
+            .with_title("a \"title\"");
+        assert_eq!(
+            Renderable::Tag(&span).try_render().unwrap(),
+            "<span id=\"item-7\" title=\"a &quot;title&quot;\"></span>"
+        );
+        let element = elements::Span::style_less(vec![])
+            .with_id("fixed")
+            .into_element();
+        assert_eq!(
+            element.into_renderable().try_render().unwrap(),
+            "<span id=\"fixed\"></span>"
+        );
+    }
+
+    #[test]
+    fn data_keys_are_checked_when_added() {
+        assert!(DataAttr::new("user-id.2_x", "").is_ok());
+        assert!(DataAttr::new("\u{e9}t\u{e9}", "").is_ok());
+        assert!(DataAttr::new("", "").is_err());
+        assert!(DataAttr::new("userId", "").is_err());
+        assert!(DataAttr::new("a b", "").is_err());
+        assert!(DataAttr::new("xmlish", "").is_err());
+        assert!(DataAttr::new("XML", "").is_err());
+        assert!(DataAttrs::empty().with("a=b", "").is_err());
+        assert!(elements::Span::style_less(vec![])
+            .add_data("Bad", "x")
+            .is_err());
+
+        let span = elements::Span::style_less(vec![])
+            .add_data("id", "1")
+            .and_then(|s| s.add_data("kind", "\"x\""))
+            .and_then(|s| s.add_data("id", "2"))
+            .unwrap();
+        assert_eq!(
+            Renderable::Tag(&span).try_render().unwrap(),
+            "<span data-id=\"2\" data-kind=\"&quot;x&quot;\"></span>"
+        );
+    }
+
+    #[test]
+    fn aria_id_refs_are_checked_when_built() {
+        assert!(units::IdRef::new("label-1").is_ok());
+        assert!(units::IdRef::new("").is_err());
+        assert!(units::IdRef::new("a b").is_err());
+        assert!(units::IdRef::new("a\u{a0}").is_err());
+
+        let ids = vec![
+            units::IdRef::new("a").unwrap(),
+            units::IdRef::new("b").unwrap(),
+        ];
+        let span = elements::Span::style_less(vec![])
+            .add_aria(AriaAttr::Labelledby(ids))
+            .add_aria(AriaAttr::Level(std::num::NonZeroU32::new(2).unwrap()))
+            .add_aria(AriaAttr::Setsize(units::AriaSizeValue::Unknown));
+        assert_eq!(
+            Renderable::Tag(&span).try_render().unwrap(),
+            "<span aria-labelledby=\"a b\" aria-level=\"2\" aria-setsize=\"-1\"></span>"
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use std::fmt;
+use std::io;
+
+use super::elements;
+use htmldsl_internal::element_traits::{RenderError, Renderable};
+
+pub enum Doctype {
+    Html5,
+    Xhtml11,
+    Xhtml1Strict,
+    Xhtml1Transitional,
+    Html401Strict,
+    Html401Transitional,
+}
+
+impl Doctype {
+    pub fn doctype_str(&self) -> String {
+        match self {
+            Doctype::Html5 => "<!DOCTYPE html>",
+            Doctype::Xhtml11 => concat!(
+                "<!DOCTYPE html PUBLIC \"-//W3C//DTD XHTML 1.1//EN\" ",
+                "\"http://www.w3.org/TR/xhtml11/DTD/xhtml11.dtd\">"
+            ),
+            Doctype::Xhtml1Strict => concat!(
+                "<!DOCTYPE html PUBLIC \"-//W3C//DTD XHTML 1.0 Strict//EN\" ",
+                "\"http://www.w3.org/TR/xhtml1/DTD/xhtml1-strict.dtd\">"
+            ),
+            Doctype::Xhtml1Transitional => concat!(
+                "<!DOCTYPE html PUBLIC \"-//W3C//DTD XHTML 1.0 Transitional//EN\" ",
+                "\"http://www.w3.org/TR/xhtml1/DTD/xhtml1-transitional.dtd\">"
+            ),
+            Doctype::Html401Strict => concat!(
+                "<!DOCTYPE HTML PUBLIC \"-//W3C//DTD HTML 4.01//EN\" ",
+                "\"http://www.w3.org/TR/html4/strict.dtd\">"
+            ),
+            Doctype::Html401Transitional => concat!(
+                "<!DOCTYPE HTML PUBLIC \"-//W3C//DTD HTML 4.01 Transitional//EN\" ",
+                "\"http://www.w3.org/TR/html4/loose.dtd\">"
+            ),
+        }
+        .into()
+    }
+}
+
+/// A full page: the doctype, an optional byte order mark, and the `<html>` element.
+pub struct Document<'a> {
+    pub doctype: Doctype,
+    pub bom: bool,
+    pub html: elements::Html<'a>,
+}
+
+impl<'a> Document<'a> {
+    pub fn new(html: elements::Html<'a>) -> Self {
+        Document {
+            doctype: Doctype::Html5,
+            bom: false,
+            html,
+        }
+    }
+
+    /// Leaves out whatever can not be rendered, see `Renderable::render`.
+    pub fn render(&self, pretty: bool) -> String {
+        let mut rendered = String::new();
+        self.render_to(pretty, &mut rendered)
+            .expect("writing to a String never fails");
+        rendered
+    }
+
+    pub fn try_render(&self, pretty: bool) -> Result<String, RenderError> {
+        let mut rendered = String::new();
+        self.try_render_to(pretty, &mut rendered)?;
+        Ok(rendered)
+    }
+
+    /// Leaves out whatever can not be rendered, see `Renderable::render`.
+    pub fn render_minified(&self) -> String {
+        let mut rendered = String::new();
+        self.write_head(&mut rendered)
+            .and_then(|_| Renderable::Tag(&self.html).render_minified_to(&mut rendered))
+            .expect("writing to a String never fails");
+        rendered
+    }
+
+    pub fn try_render_minified(&self) -> Result<String, RenderError> {
+        let mut rendered = String::new();
+        self.try_render_minified_to(&mut rendered)?;
+        Ok(rendered)
+    }
+
+    pub fn try_render_minified_to<W: fmt::Write>(&self, w: &mut W) -> Result<(), RenderError> {
+        self.write_head(w)?;
+        Renderable::Tag(&self.html).try_render_minified_to(w)
+    }
+
+    /// Leaves out whatever can not be rendered, see `Renderable::render`.
+    pub fn render_xhtml(&self) -> String {
+        let mut rendered = String::new();
+        self.write_xml_head(&mut rendered)
+            .and_then(|_| Renderable::Tag(&self.html).render_xhtml_to(&mut rendered))
+            .expect("writing to a String never fails");
+        rendered
+    }
+
+    /// Renders the page as well formed xml, prefixed with an xml declaration. Use one of the
+    /// xhtml doctypes (or html5) for the page to be valid polyglot markup.
+    pub fn try_render_xhtml(&self) -> Result<String, RenderError> {
+        let mut rendered = String::new();
+        self.try_render_xhtml_to(&mut rendered)?;
+        Ok(rendered)
+    }
+
+    pub fn try_render_xhtml_to<W: fmt::Write>(&self, w: &mut W) -> Result<(), RenderError> {
+        self.write_xml_head(w)?;
+        Renderable::Tag(&self.html).try_render_xhtml_to(w)
+    }
+
+    /// Leaves out whatever can not be rendered, see `Renderable::render`.
+    pub fn render_to<W: fmt::Write>(&self, pretty: bool, w: &mut W) -> fmt::Result {
+        self.write_head(w)?;
+        let tag = Renderable::Tag(&self.html);
+        match pretty {
+            true => tag.render_pretty_to(w),
+            false => tag.render_to(w),
+        }
+    }
+
+    pub fn try_render_to<W: fmt::Write>(&self, pretty: bool, w: &mut W) -> Result<(), RenderError> {
+        self.write_head(w)?;
+        let tag = Renderable::Tag(&self.html);
+        match pretty {
+            true => tag.try_render_pretty_to(w),
+            false => tag.try_render_to(w),
+        }
+    }
+
+    pub fn render_to_io<W: io::Write>(&self, pretty: bool, w: &mut W) -> io::Result<()> {
+        if self.bom {
+            w.write_all("\u{feff}".as_bytes())?;
+        }
+        w.write_all(self.doctype.doctype_str().as_bytes())?;
+        let tag = Renderable::Tag(&self.html);
+        match pretty {
+            true => tag.render_pretty_to_io(w),
+            false => tag.render_to_io(w),
+        }
+    }
+
+    pub fn render_minified_to_io<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        if self.bom {
+            w.write_all("\u{feff}".as_bytes())?;
+        }
+        w.write_all(self.doctype.doctype_str().as_bytes())?;
+        Renderable::Tag(&self.html).render_minified_to_io(w)
+    }
+
+    pub fn render_xhtml_to_io<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        if self.bom {
+            w.write_all("\u{feff}".as_bytes())?;
+        }
+        w.write_all("<?xml version=\"1.0\" encoding=\"UTF-8\"?>".as_bytes())?;
+        w.write_all(self.doctype.doctype_str().as_bytes())?;
+        Renderable::Tag(&self.html).render_xhtml_to_io(w)
+    }
+
+    /// The byte order mark, if any, and the doctype.
+    fn write_head<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        if self.bom {
+            w.write_char('\u{feff}')?;
+        }
+        w.write_str(&self.doctype.doctype_str())
+    }
+
+    fn write_xml_head<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        if self.bom {
+            w.write_char('\u{feff}')?;
+        }
+        w.write_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+        w.write_str(&self.doctype.doctype_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attributes::{self, TagRenderableGlobalAttrsSetter};
+    use crate::units;
+    use htmldsl_internal::element_traits::{text, TagRenderableIntoElement};
+
+    fn page() -> Document<'static> {
+        Document {
+            doctype: Doctype::Xhtml1Strict,
+            bom: false,
+            html: elements::Html::style_less(
+                None,
+                Some(elements::Body::style_less(vec![elements::P::style_less(
+                    vec![
+                        text("1 < 2 & \"3\""),
+                        elements::Img::style_less_with_src("a.png".into()).into_element(),
+                    ],
+                )
+                .with_hidden()
+                .with_title("say \"hi\"\n'there'")
+                .into_element()])),
+                attributes::Lang {
+                    tag: units::LanguageTag::parse("en").unwrap(),
+                },
+            ),
+        }
+    }
+
+    #[test]
+    fn xhtml_is_well_formed() {
+        let expected = concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>",
+            "<!DOCTYPE html PUBLIC \"-//W3C//DTD XHTML 1.0 Strict//EN\" ",
+            "\"http://www.w3.org/TR/xhtml1/DTD/xhtml1-strict.dtd\">",
+            "<html lang=\"en\" xmlns=\"http://www.w3.org/1999/xhtml\"><body>",
+            "<p title=\"say &quot;hi&quot;&#10;&apos;there&apos;\" hidden=\"hidden\">",
+            "1 &lt; 2 &amp; \"3\"<img src=\"a.png\"/></p>",
+            "</body></html>"
+        );
+        let document = page();
+        assert_eq!(document.try_render_xhtml().unwrap(), expected);
+        assert_eq!(document.render_xhtml(), expected);
+
+        let mut bytes = Vec::new();
+        document.render_xhtml_to_io(&mut bytes).unwrap();
+        assert_eq!(bytes, expected.as_bytes());
+    }
+}
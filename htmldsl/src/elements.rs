@@ -1,32 +1,22 @@
 use super::attributes;
 use super::style_sheet;
 use super::units;
-use htmldsl_internal::attr_traits::Attribute;
-use htmldsl_internal::element_traits::{
-    Element, Renderable, TagRenderableAttrs, TagRenderableChildren,
-};
+use htmldsl_internal::element_traits::{Element, RenderError, Renderable, TagRenderableChildren};
+use htmldsl_internal::escape;
 
-mod util {
-    use crate::attributes;
-    use htmldsl_internal::attr_traits::Attribute;
-
-    pub fn full_attrs<'a>(
-        mut attrs: Vec<&'a dyn Attribute>,
-        styles: &'a attributes::StyleAttr<'a>,
-    ) -> Vec<&'a dyn Attribute> {
-        if styles.values.len() > 0 {
-            attrs.push(styles);
-        }
-        attrs
-    }
-}
-
-#[derive(TagRenderable, TagRenderableName, TagRenderableStyleSetter)]
+#[derive(
+    TagRenderable,
+    TagRenderableName,
+    TagRenderableAttrs,
+    TagRenderableGlobalAttrsSetter,
+    TagRenderableStyleSetter,
+)]
 #[tag_renderable_name(name = "html")]
 pub struct Html<'a> {
     pub head: Option<Head<'a>>,
     pub body: Option<Body<'a>>,
-    pub lang: attributes::Lang,
+    #[tag_renderable_global_attrs]
+    pub global_attrs: attributes::GlobalAttrs,
     #[tag_renderable_style]
     pub styles: attributes::StyleAttr<'a>,
 }
@@ -38,59 +28,51 @@ impl<'a> Html<'a> {
         lang: attributes::Lang,
     ) -> Self {
         Html {
-            head: head,
-            body: body,
-            lang: lang,
+            head,
+            body,
+            global_attrs: attributes::GlobalAttrs {
+                lang: Some(lang),
+                ..attributes::GlobalAttrs::empty()
+            },
             styles: attributes::StyleAttr::empty(),
         }
     }
 }
 
-impl<'a> TagRenderableAttrs for Html<'a> {
-    fn get_attributes(&self) -> Vec<&dyn Attribute> {
-        util::full_attrs(vec![&self.lang], &self.styles)
-    }
-}
-
 impl<'a> TagRenderableChildren for Html<'a> {
-    fn get_children(&self) -> Result<Vec<Renderable>, String> {
+    fn get_children(&self) -> Result<Vec<Renderable<'_>>, RenderError> {
         let mut ret: Vec<Renderable> = Vec::new();
-        match self.head {
-            Some(ref v) => ret.push(Renderable::Tag(v)),
-            None => (),
+        if let Some(ref v) = self.head {
+            ret.push(Renderable::Tag(v))
         }
-        match self.body {
-            Some(ref v) => ret.push(Renderable::Tag(v)),
-            None => (),
+        if let Some(ref v) = self.body {
+            ret.push(Renderable::Tag(v))
         }
         Ok(ret)
     }
 }
 
-#[derive(TagRenderable, TagRenderableName)]
+#[derive(TagRenderable, TagRenderableName, TagRenderableAttrs, TagRenderableGlobalAttrsSetter)]
 #[tag_renderable_name(name = "head")]
 pub struct Head<'a> {
     pub metas: Vec<Meta<'a>>,
     pub styles: Vec<Style<'a>>,
+    #[tag_renderable_global_attrs]
+    pub global_attrs: attributes::GlobalAttrs,
 }
 
 impl<'a> Head<'a> {
     pub fn new(metas: Vec<Meta<'a>>, styles: Vec<Style<'a>>) -> Self {
         Head {
-            metas: metas,
-            styles: styles,
+            metas,
+            styles,
+            global_attrs: attributes::GlobalAttrs::empty(),
         }
     }
 }
 
-impl<'a> TagRenderableAttrs for Head<'a> {
-    fn get_attributes(&self) -> Vec<&dyn Attribute> {
-        Vec::new()
-    }
-}
-
 impl<'a> TagRenderableChildren for Head<'a> {
-    fn get_children(&self) -> Result<Vec<Renderable>, String> {
+    fn get_children(&self) -> Result<Vec<Renderable<'_>>, RenderError> {
         let mut ret: Vec<Renderable> = Vec::new();
         for m in self.metas.iter() {
             ret.push(Renderable::Tag(m));
@@ -102,10 +84,19 @@ impl<'a> TagRenderableChildren for Head<'a> {
     }
 }
 
-#[derive(TagRenderable, TagRenderableName, TagRenderableStyleSetter)]
-#[tag_renderable_name(name = "meta")]
+#[derive(
+    TagRenderable,
+    TagRenderableName,
+    TagRenderableAttrs,
+    TagRenderableGlobalAttrsSetter,
+    TagRenderableStyleSetter,
+)]
+#[tag_renderable_name(name = "meta", void)]
 pub struct Meta<'a> {
+    #[tag_renderable_attr]
     pub charset: Option<attributes::Charset>,
+    #[tag_renderable_global_attrs]
+    pub global_attrs: attributes::GlobalAttrs,
     #[tag_renderable_style]
     pub styles: attributes::StyleAttr<'a>,
 }
@@ -113,295 +104,283 @@ pub struct Meta<'a> {
 impl<'a> Meta<'a> {
     pub fn style_less(charset: Option<attributes::Charset>) -> Self {
         Meta {
-            charset: charset,
+            charset,
+            global_attrs: attributes::GlobalAttrs::empty(),
             styles: attributes::StyleAttr::empty(),
         }
     }
 }
 
-impl<'a> TagRenderableAttrs for Meta<'a> {
-    fn get_attributes(&self) -> Vec<&dyn Attribute> {
-        util::full_attrs(
-            self.charset.as_ref().map_or(Vec::new(), |v| vec![v]),
-            &self.styles,
-        )
-    }
-}
-
-impl<'a> TagRenderableChildren for Meta<'a> {
-    fn get_children(&self) -> Result<Vec<Renderable>, String> {
-        Ok(vec![])
-    }
-}
-
-#[derive(TagRenderable, TagRenderableName)]
+#[derive(TagRenderable, TagRenderableName, TagRenderableAttrs, TagRenderableGlobalAttrsSetter)]
 #[tag_renderable_name(name = "style")]
 pub struct Style<'a> {
     pub style_sheet: style_sheet::StyleSheet<'a>,
+    #[tag_renderable_global_attrs]
+    pub global_attrs: attributes::GlobalAttrs,
 }
 
-impl<'a> TagRenderableAttrs for Style<'a> {
-    fn get_attributes(&self) -> Vec<&dyn Attribute> {
-        Vec::new()
+impl<'a> Style<'a> {
+    pub fn new(style_sheet: style_sheet::StyleSheet<'a>) -> Self {
+        Style {
+            style_sheet,
+            global_attrs: attributes::GlobalAttrs::empty(),
+        }
     }
 }
 
 impl<'a> TagRenderableChildren for Style<'a> {
-    fn get_children(&self) -> Result<Vec<Renderable>, String> {
-        Err(style_sheet::style_sheet_string(&self.style_sheet))
+    fn get_children(&self) -> Result<Vec<Renderable<'_>>, RenderError> {
+        Ok(vec![Renderable::Raw(
+            escape::escape_raw_text("style", &style_sheet::style_sheet_string(&self.style_sheet))
+                .into(),
+        )])
     }
 }
 
-#[derive(TagRenderableName, TagRenderable, TagRenderableChildren, TagRenderableStyleSetter)]
+#[derive(
+    TagRenderableName,
+    TagRenderable,
+    TagRenderableChildren,
+    TagRenderableAttrs,
+    TagRenderableGlobalAttrsSetter,
+    TagRenderableStyleSetter,
+)]
 #[tag_renderable_name(name = "body")]
 pub struct Body<'a> {
     #[tag_renderable_children(type = "element")]
-    pub children: Vec<Element>,
+    pub children: Vec<Element<'a>>,
+    #[tag_renderable_global_attrs]
+    pub global_attrs: attributes::GlobalAttrs,
     #[tag_renderable_style]
     pub styles: attributes::StyleAttr<'a>,
 }
 
 impl<'a> Body<'a> {
-    pub fn style_less(children: Vec<Element>) -> Self {
+    pub fn style_less(children: Vec<Element<'a>>) -> Self {
         Body {
-            children: children,
+            children,
+            global_attrs: attributes::GlobalAttrs::empty(),
             styles: attributes::StyleAttr::empty(),
         }
     }
 }
 
-impl<'a> TagRenderableAttrs for Body<'a> {
-    fn get_attributes(&self) -> Vec<&dyn Attribute> {
-        util::full_attrs(vec![], &self.styles)
-    }
-}
-
 #[derive(
     TagRenderableName,
     GenericRenderable,
     TagRenderable,
     TagRenderableChildren,
+    TagRenderableAttrs,
+    TagRenderableGlobalAttrsSetter,
     TagRenderableStyleSetter,
     TagRenderableIntoElement,
 )]
 #[tag_renderable_name(name = "div")]
 pub struct Div<'a> {
-    pub id: Option<attributes::Id>,
     #[tag_renderable_children(type = "element")]
-    pub children: Vec<Element>,
+    pub children: Vec<Element<'a>>,
+    #[tag_renderable_global_attrs]
+    pub global_attrs: attributes::GlobalAttrs,
     #[tag_renderable_style]
     pub styles: attributes::StyleAttr<'a>,
 }
 
 impl<'a> Div<'a> {
-    pub fn style_less(children: Vec<Element>) -> Self {
+    pub fn style_less(children: Vec<Element<'a>>) -> Self {
         Div {
-            id: None,
-            children: children,
+            children,
+            global_attrs: attributes::GlobalAttrs::empty(),
             styles: attributes::StyleAttr::empty(),
         }
     }
 }
 
-impl<'a> TagRenderableAttrs for Div<'a> {
-    fn get_attributes(&self) -> Vec<&dyn Attribute> {
-        util::full_attrs(
-            self.id.as_ref().map_or(Vec::new(), |v| vec![v]),
-            &self.styles,
-        )
-    }
-}
-
 #[derive(
     TagRenderableName,
     GenericRenderable,
     TagRenderable,
     TagRenderableChildren,
+    TagRenderableAttrs,
+    TagRenderableGlobalAttrsSetter,
     TagRenderableStyleSetter,
     TagRenderableIntoElement,
 )]
 #[tag_renderable_name(name = "span")]
 pub struct Span<'a> {
     #[tag_renderable_children(type = "element")]
-    pub children: Vec<Element>,
+    pub children: Vec<Element<'a>>,
+    #[tag_renderable_global_attrs]
+    pub global_attrs: attributes::GlobalAttrs,
     #[tag_renderable_style]
     pub styles: attributes::StyleAttr<'a>,
 }
 
 impl<'a> Span<'a> {
-    pub fn style_less(children: Vec<Element>) -> Self {
+    pub fn style_less(children: Vec<Element<'a>>) -> Self {
         Span {
-            children: children,
+            children,
+            global_attrs: attributes::GlobalAttrs::empty(),
             styles: attributes::StyleAttr::empty(),
         }
     }
 }
 
-impl<'a> TagRenderableAttrs for Span<'a> {
-    fn get_attributes(&self) -> Vec<&dyn Attribute> {
-        util::full_attrs(vec![], &self.styles)
-    }
-}
-
 #[derive(
     TagRenderableName,
     GenericRenderable,
     TagRenderable,
     TagRenderableChildren,
+    TagRenderableAttrs,
+    TagRenderableGlobalAttrsSetter,
     TagRenderableStyleSetter,
     TagRenderableIntoElement,
 )]
 #[tag_renderable_name(name = "h1")]
 pub struct H1<'a> {
     #[tag_renderable_children(type = "element")]
-    pub children: Vec<Element>,
+    pub children: Vec<Element<'a>>,
+    #[tag_renderable_global_attrs]
+    pub global_attrs: attributes::GlobalAttrs,
     #[tag_renderable_style]
     pub styles: attributes::StyleAttr<'a>,
 }
 
 impl<'a> H1<'a> {
-    pub fn style_less(children: Vec<Element>) -> Self {
+    pub fn style_less(children: Vec<Element<'a>>) -> Self {
         H1 {
-            children: children,
+            children,
+            global_attrs: attributes::GlobalAttrs::empty(),
             styles: attributes::StyleAttr::empty(),
         }
     }
 }
 
-impl<'a> TagRenderableAttrs for H1<'a> {
-    fn get_attributes(&self) -> Vec<&dyn Attribute> {
-        util::full_attrs(vec![], &self.styles)
-    }
-}
-
 #[derive(
     TagRenderableName,
     GenericRenderable,
     TagRenderable,
     TagRenderableChildren,
+    TagRenderableAttrs,
+    TagRenderableGlobalAttrsSetter,
     TagRenderableStyleSetter,
     TagRenderableIntoElement,
 )]
 #[tag_renderable_name(name = "h2")]
 pub struct H2<'a> {
     #[tag_renderable_children(type = "element")]
-    pub children: Vec<Element>,
+    pub children: Vec<Element<'a>>,
+    #[tag_renderable_global_attrs]
+    pub global_attrs: attributes::GlobalAttrs,
     #[tag_renderable_style]
     pub styles: attributes::StyleAttr<'a>,
 }
 
 impl<'a> H2<'a> {
-    pub fn style_less(children: Vec<Element>) -> Self {
+    pub fn style_less(children: Vec<Element<'a>>) -> Self {
         H2 {
-            children: children,
+            children,
+            global_attrs: attributes::GlobalAttrs::empty(),
             styles: attributes::StyleAttr::empty(),
         }
     }
 }
 
-impl<'a> TagRenderableAttrs for H2<'a> {
-    fn get_attributes(&self) -> Vec<&dyn Attribute> {
-        util::full_attrs(vec![], &self.styles)
-    }
-}
-
 #[derive(
     TagRenderableName,
     GenericRenderable,
     TagRenderable,
     TagRenderableChildren,
+    TagRenderableAttrs,
+    TagRenderableGlobalAttrsSetter,
     TagRenderableStyleSetter,
     TagRenderableIntoElement,
 )]
 #[tag_renderable_name(name = "h3")]
 pub struct H3<'a> {
     #[tag_renderable_children(type = "element")]
-    pub children: Vec<Element>,
+    pub children: Vec<Element<'a>>,
+    #[tag_renderable_global_attrs]
+    pub global_attrs: attributes::GlobalAttrs,
     #[tag_renderable_style]
     pub styles: attributes::StyleAttr<'a>,
 }
 
 impl<'a> H3<'a> {
-    pub fn style_less(children: Vec<Element>) -> Self {
+    pub fn style_less(children: Vec<Element<'a>>) -> Self {
         H3 {
-            children: children,
+            children,
+            global_attrs: attributes::GlobalAttrs::empty(),
             styles: attributes::StyleAttr::empty(),
         }
     }
 }
 
-impl<'a> TagRenderableAttrs for H3<'a> {
-    fn get_attributes(&self) -> Vec<&dyn Attribute> {
-        util::full_attrs(vec![], &self.styles)
-    }
-}
-
 #[derive(
     TagRenderableName,
     GenericRenderable,
     TagRenderable,
     TagRenderableChildren,
+    TagRenderableAttrs,
+    TagRenderableGlobalAttrsSetter,
     TagRenderableStyleSetter,
     TagRenderableIntoElement,
 )]
 #[tag_renderable_name(name = "h4")]
 pub struct H4<'a> {
     #[tag_renderable_children(type = "element")]
-    pub children: Vec<Element>,
+    pub children: Vec<Element<'a>>,
+    #[tag_renderable_global_attrs]
+    pub global_attrs: attributes::GlobalAttrs,
     #[tag_renderable_style]
     pub styles: attributes::StyleAttr<'a>,
 }
 
 impl<'a> H4<'a> {
-    pub fn style_less(children: Vec<Element>) -> Self {
+    pub fn style_less(children: Vec<Element<'a>>) -> Self {
         H4 {
-            children: children,
+            children,
+            global_attrs: attributes::GlobalAttrs::empty(),
             styles: attributes::StyleAttr::empty(),
         }
     }
 }
 
-impl<'a> TagRenderableAttrs for H4<'a> {
-    fn get_attributes(&self) -> Vec<&dyn Attribute> {
-        util::full_attrs(vec![], &self.styles)
-    }
-}
-
 #[derive(
     TagRenderableName,
     GenericRenderable,
     TagRenderable,
     TagRenderableChildren,
+    TagRenderableAttrs,
+    TagRenderableGlobalAttrsSetter,
     TagRenderableStyleSetter,
     TagRenderableIntoElement,
 )]
 #[tag_renderable_name(name = "p")]
 pub struct P<'a> {
     #[tag_renderable_children(type = "element")]
-    pub children: Vec<Element>,
+    pub children: Vec<Element<'a>>,
+    #[tag_renderable_global_attrs]
+    pub global_attrs: attributes::GlobalAttrs,
     #[tag_renderable_style]
     pub styles: attributes::StyleAttr<'a>,
 }
 
 impl<'a> P<'a> {
-    pub fn style_less(children: Vec<Element>) -> Self {
+    pub fn style_less(children: Vec<Element<'a>>) -> Self {
         P {
-            children: children,
+            children,
+            global_attrs: attributes::GlobalAttrs::empty(),
             styles: attributes::StyleAttr::empty(),
         }
     }
 }
 
-impl<'a> TagRenderableAttrs for P<'a> {
-    fn get_attributes(&self) -> Vec<&dyn Attribute> {
-        util::full_attrs(vec![], &self.styles)
-    }
-}
-
 #[derive(
     TagRenderableName,
     GenericRenderable,
     TagRenderable,
+    TagRenderableAttrs,
+    TagRenderableGlobalAttrsSetter,
     TagRenderableStyleSetter,
     TagRenderableIntoElement,
 )]
@@ -409,6 +388,8 @@ impl<'a> TagRenderableAttrs for P<'a> {
 pub struct Table<'a> {
     pub thead: Option<Thead<'a>>,
     pub tbody: Tbody<'a>,
+    #[tag_renderable_global_attrs]
+    pub global_attrs: attributes::GlobalAttrs,
     #[tag_renderable_style]
     pub styles: attributes::StyleAttr<'a>,
 }
@@ -416,19 +397,20 @@ pub struct Table<'a> {
 impl<'a> Table<'a> {
     pub fn style_less(thead: Option<Thead<'a>>, tbody: Tbody<'a>) -> Self {
         Table {
-            thead: thead,
-            tbody: tbody,
+            thead,
+            tbody,
+            global_attrs: attributes::GlobalAttrs::empty(),
             styles: attributes::StyleAttr::empty(),
         }
     }
 
     pub fn style_less_from_vecs(
-        thead: Option<Vec<Vec<Element>>>,
-        tbody: Vec<Vec<Element>>,
+        thead: Option<Vec<Vec<Element<'a>>>>,
+        tbody: Vec<Vec<Element<'a>>>,
     ) -> Self {
         Table {
-            thead: match thead {
-                Some(h) => Some(Thead::style_less(
+            thead: thead.map(|h| {
+                Thead::style_less(
                     h.into_iter()
                         .map(|row| {
                             Thr::style_less(
@@ -438,9 +420,8 @@ impl<'a> Table<'a> {
                             )
                         })
                         .collect(),
-                )),
-                None => None,
-            },
+                )
+            }),
             tbody: Tbody::style_less(
                 tbody
                     .into_iter()
@@ -453,34 +434,37 @@ impl<'a> Table<'a> {
                     })
                     .collect(),
             ),
+            global_attrs: attributes::GlobalAttrs::empty(),
             styles: attributes::StyleAttr::empty(),
         }
     }
 }
 
-impl<'a> TagRenderableAttrs for Table<'a> {
-    fn get_attributes(&self) -> Vec<&dyn Attribute> {
-        util::full_attrs(vec![], &self.styles)
-    }
-}
-
 impl<'a> TagRenderableChildren for Table<'a> {
-    fn get_children(&self) -> Result<Vec<Renderable>, String> {
+    fn get_children(&self) -> Result<Vec<Renderable<'_>>, RenderError> {
         let mut ret: Vec<Renderable> = Vec::new();
-        match self.thead {
-            Some(ref v) => ret.push(Renderable::Tag(v)),
-            None => (),
+        if let Some(ref v) = self.thead {
+            ret.push(Renderable::Tag(v))
         }
         ret.push(Renderable::Tag(&self.tbody));
         Ok(ret)
     }
 }
 
-#[derive(TagRenderableName, TagRenderable, TagRenderableChildren, TagRenderableStyleSetter)]
+#[derive(
+    TagRenderableName,
+    TagRenderable,
+    TagRenderableChildren,
+    TagRenderableAttrs,
+    TagRenderableGlobalAttrsSetter,
+    TagRenderableStyleSetter,
+)]
 #[tag_renderable_name(name = "thead")]
 pub struct Thead<'a> {
     #[tag_renderable_children(type = "renderable")]
     pub trs: Vec<Thr<'a>>,
+    #[tag_renderable_global_attrs]
+    pub global_attrs: attributes::GlobalAttrs,
     #[tag_renderable_style]
     pub styles: attributes::StyleAttr<'a>,
 }
@@ -488,23 +472,27 @@ pub struct Thead<'a> {
 impl<'a> Thead<'a> {
     pub fn style_less(trs: Vec<Thr<'a>>) -> Self {
         Thead {
-            trs: trs,
+            trs,
+            global_attrs: attributes::GlobalAttrs::empty(),
             styles: attributes::StyleAttr::empty(),
         }
     }
 }
 
-impl<'a> TagRenderableAttrs for Thead<'a> {
-    fn get_attributes(&self) -> Vec<&dyn Attribute> {
-        util::full_attrs(vec![], &self.styles)
-    }
-}
-
-#[derive(TagRenderableName, TagRenderable, TagRenderableChildren, TagRenderableStyleSetter)]
+#[derive(
+    TagRenderableName,
+    TagRenderable,
+    TagRenderableChildren,
+    TagRenderableAttrs,
+    TagRenderableGlobalAttrsSetter,
+    TagRenderableStyleSetter,
+)]
 #[tag_renderable_name(name = "tr")]
 pub struct Thr<'a> {
     #[tag_renderable_children(type = "renderable")]
     pub ths: Vec<Th<'a>>,
+    #[tag_renderable_global_attrs]
+    pub global_attrs: attributes::GlobalAttrs,
     #[tag_renderable_style]
     pub styles: attributes::StyleAttr<'a>,
 }
@@ -512,47 +500,55 @@ pub struct Thr<'a> {
 impl<'a> Thr<'a> {
     pub fn style_less(ths: Vec<Th<'a>>) -> Self {
         Thr {
-            ths: ths,
+            ths,
+            global_attrs: attributes::GlobalAttrs::empty(),
             styles: attributes::StyleAttr::empty(),
         }
     }
 }
 
-impl<'a> TagRenderableAttrs for Thr<'a> {
-    fn get_attributes(&self) -> Vec<&dyn Attribute> {
-        util::full_attrs(vec![], &self.styles)
-    }
-}
-
-#[derive(TagRenderableName, TagRenderable, TagRenderableChildren, TagRenderableStyleSetter)]
+#[derive(
+    TagRenderableName,
+    TagRenderable,
+    TagRenderableChildren,
+    TagRenderableAttrs,
+    TagRenderableGlobalAttrsSetter,
+    TagRenderableStyleSetter,
+)]
 #[tag_renderable_name(name = "th")]
 pub struct Th<'a> {
     #[tag_renderable_children(type = "element")]
-    pub children: Vec<Element>,
+    pub children: Vec<Element<'a>>,
+    #[tag_renderable_global_attrs]
+    pub global_attrs: attributes::GlobalAttrs,
     #[tag_renderable_style]
     pub styles: attributes::StyleAttr<'a>,
 }
 
 impl<'a> Th<'a> {
-    pub fn style_less(children: Vec<Element>) -> Self {
+    pub fn style_less(children: Vec<Element<'a>>) -> Self {
         Th {
-            children: children,
+            children,
+            global_attrs: attributes::GlobalAttrs::empty(),
             styles: attributes::StyleAttr::empty(),
         }
     }
 }
 
-impl<'a> TagRenderableAttrs for Th<'a> {
-    fn get_attributes(&self) -> Vec<&dyn Attribute> {
-        util::full_attrs(vec![], &self.styles)
-    }
-}
-
-#[derive(TagRenderableName, TagRenderable, TagRenderableChildren, TagRenderableStyleSetter)]
+#[derive(
+    TagRenderableName,
+    TagRenderable,
+    TagRenderableChildren,
+    TagRenderableAttrs,
+    TagRenderableGlobalAttrsSetter,
+    TagRenderableStyleSetter,
+)]
 #[tag_renderable_name(name = "tbody")]
 pub struct Tbody<'a> {
     #[tag_renderable_children(type = "renderable")]
     pub trs: Vec<Tr<'a>>,
+    #[tag_renderable_global_attrs]
+    pub global_attrs: attributes::GlobalAttrs,
     #[tag_renderable_style]
     pub styles: attributes::StyleAttr<'a>,
 }
@@ -560,23 +556,27 @@ pub struct Tbody<'a> {
 impl<'a> Tbody<'a> {
     pub fn style_less(trs: Vec<Tr<'a>>) -> Self {
         Tbody {
-            trs: trs,
+            trs,
+            global_attrs: attributes::GlobalAttrs::empty(),
             styles: attributes::StyleAttr::empty(),
         }
     }
 }
 
-impl<'a> TagRenderableAttrs for Tbody<'a> {
-    fn get_attributes(&self) -> Vec<&dyn Attribute> {
-        util::full_attrs(vec![], &self.styles)
-    }
-}
-
-#[derive(TagRenderableName, TagRenderable, TagRenderableChildren, TagRenderableStyleSetter)]
+#[derive(
+    TagRenderableName,
+    TagRenderable,
+    TagRenderableChildren,
+    TagRenderableAttrs,
+    TagRenderableGlobalAttrsSetter,
+    TagRenderableStyleSetter,
+)]
 #[tag_renderable_name(name = "tr")]
 pub struct Tr<'a> {
     #[tag_renderable_children(type = "renderable")]
     pub tds: Vec<Td<'a>>,
+    #[tag_renderable_global_attrs]
+    pub global_attrs: attributes::GlobalAttrs,
     #[tag_renderable_style]
     pub styles: attributes::StyleAttr<'a>,
 }
@@ -584,114 +584,116 @@ pub struct Tr<'a> {
 impl<'a> Tr<'a> {
     pub fn style_less(tds: Vec<Td<'a>>) -> Self {
         Tr {
-            tds: tds,
+            tds,
+            global_attrs: attributes::GlobalAttrs::empty(),
             styles: attributes::StyleAttr::empty(),
         }
     }
 }
 
-impl<'a> TagRenderableAttrs for Tr<'a> {
-    fn get_attributes(&self) -> Vec<&dyn Attribute> {
-        util::full_attrs(vec![], &self.styles)
-    }
-}
-
-#[derive(TagRenderableName, TagRenderable, TagRenderableChildren, TagRenderableStyleSetter)]
+#[derive(
+    TagRenderableName,
+    TagRenderable,
+    TagRenderableChildren,
+    TagRenderableAttrs,
+    TagRenderableGlobalAttrsSetter,
+    TagRenderableStyleSetter,
+)]
 #[tag_renderable_name(name = "td")]
 pub struct Td<'a> {
     #[tag_renderable_children(type = "element")]
-    pub children: Vec<Element>,
+    pub children: Vec<Element<'a>>,
+    #[tag_renderable_global_attrs]
+    pub global_attrs: attributes::GlobalAttrs,
     #[tag_renderable_style]
     pub styles: attributes::StyleAttr<'a>,
 }
 
 impl<'a> Td<'a> {
-    pub fn style_less(children: Vec<Element>) -> Self {
+    pub fn style_less(children: Vec<Element<'a>>) -> Self {
         Td {
-            children: children,
+            children,
+            global_attrs: attributes::GlobalAttrs::empty(),
             styles: attributes::StyleAttr::empty(),
         }
     }
 }
 
-impl<'a> TagRenderableAttrs for Td<'a> {
-    fn get_attributes(&self) -> Vec<&dyn Attribute> {
-        util::full_attrs(vec![], &self.styles)
-    }
-}
-
 #[derive(
     TagRenderableName,
     GenericRenderable,
     TagRenderable,
     TagRenderableChildren,
+    TagRenderableAttrs,
+    TagRenderableGlobalAttrsSetter,
     TagRenderableStyleSetter,
     TagRenderableIntoElement,
 )]
 #[tag_renderable_name(name = "code")]
 pub struct Code<'a> {
     #[tag_renderable_children(type = "element")]
-    pub children: Vec<Element>,
+    pub children: Vec<Element<'a>>,
+    #[tag_renderable_global_attrs]
+    pub global_attrs: attributes::GlobalAttrs,
     #[tag_renderable_style]
     pub styles: attributes::StyleAttr<'a>,
 }
 
 impl<'a> Code<'a> {
-    pub fn style_less(children: Vec<Element>) -> Self {
+    pub fn style_less(children: Vec<Element<'a>>) -> Self {
         Code {
-            children: children,
+            children,
+            global_attrs: attributes::GlobalAttrs::empty(),
             styles: attributes::StyleAttr::empty(),
         }
     }
 }
 
-impl<'a> TagRenderableAttrs for Code<'a> {
-    fn get_attributes(&self) -> Vec<&dyn Attribute> {
-        util::full_attrs(vec![], &self.styles)
-    }
-}
-
 #[derive(
     TagRenderableName,
     GenericRenderable,
     TagRenderable,
     TagRenderableChildren,
+    TagRenderableAttrs,
+    TagRenderableGlobalAttrsSetter,
     TagRenderableStyleSetter,
     TagRenderableIntoElement,
 )]
 #[tag_renderable_name(name = "pre")]
 pub struct Pre<'a> {
     #[tag_renderable_children(type = "element")]
-    pub children: Vec<Element>,
+    pub children: Vec<Element<'a>>,
+    #[tag_renderable_global_attrs]
+    pub global_attrs: attributes::GlobalAttrs,
     #[tag_renderable_style]
     pub styles: attributes::StyleAttr<'a>,
 }
 
 impl<'a> Pre<'a> {
-    pub fn style_less(children: Vec<Element>) -> Self {
+    pub fn style_less(children: Vec<Element<'a>>) -> Self {
         Pre {
-            children: children,
+            children,
+            global_attrs: attributes::GlobalAttrs::empty(),
             styles: attributes::StyleAttr::empty(),
         }
     }
 }
 
-impl<'a> TagRenderableAttrs for Pre<'a> {
-    fn get_attributes(&self) -> Vec<&dyn Attribute> {
-        util::full_attrs(vec![], &self.styles)
-    }
-}
-
 #[derive(
     TagRenderableName,
     GenericRenderable,
     TagRenderable,
+    TagRenderableAttrs,
+    TagRenderableGlobalAttrsSetter,
     TagRenderableStyleSetter,
     TagRenderableIntoElement,
 )]
-#[tag_renderable_name(name = "img")]
+#[tag_renderable_name(name = "img", void)]
 pub struct Img<'a> {
+    #[tag_renderable_attr]
     pub src: attributes::Src,
+    #[tag_renderable_global_attrs]
+    pub global_attrs: attributes::GlobalAttrs,
     #[tag_renderable_style]
     pub styles: attributes::StyleAttr<'a>,
 }
@@ -699,7 +701,8 @@ pub struct Img<'a> {
 impl<'a> Img<'a> {
     pub fn style_less(src: attributes::Src) -> Self {
         Img {
-            src: src,
+            src,
+            global_attrs: attributes::GlobalAttrs::empty(),
             styles: attributes::StyleAttr::empty(),
         }
     }
@@ -709,69 +712,64 @@ impl<'a> Img<'a> {
             src: attributes::Src {
                 value: units::SourceValue::new(src),
             },
+            global_attrs: attributes::GlobalAttrs::empty(),
             styles: attributes::StyleAttr::empty(),
         }
     }
 }
 
-impl<'a> TagRenderableAttrs for Img<'a> {
-    fn get_attributes(&self) -> Vec<&dyn Attribute> {
-        util::full_attrs(vec![&self.src], &self.styles)
-    }
-}
-
-impl<'a> TagRenderableChildren for Img<'a> {
-    fn get_children(&self) -> Result<Vec<Renderable>, String> {
-        Ok(Vec::new())
-    }
-}
-
 #[derive(
     TagRenderableName,
     GenericRenderable,
     TagRenderable,
     TagRenderableChildren,
+    TagRenderableAttrs,
+    TagRenderableGlobalAttrsSetter,
     TagRenderableStyleSetter,
     TagRenderableIntoElement,
 )]
 #[tag_renderable_name(name = "a")]
 pub struct A<'a> {
+    #[tag_renderable_attr]
     pub href: attributes::Href,
     #[tag_renderable_children(type = "element")]
-    pub children: Vec<Element>,
+    pub children: Vec<Element<'a>>,
+    #[tag_renderable_global_attrs]
+    pub global_attrs: attributes::GlobalAttrs,
     #[tag_renderable_style]
     pub styles: attributes::StyleAttr<'a>,
 }
 
 impl<'a> A<'a> {
-    pub fn style_less(href: attributes::Href, children: Vec<Element>) -> Self {
+    pub fn style_less(href: attributes::Href, children: Vec<Element<'a>>) -> Self {
         A {
-            href: href,
-            children: children,
+            href,
+            children,
+            global_attrs: attributes::GlobalAttrs::empty(),
             styles: attributes::StyleAttr::empty(),
         }
     }
 }
 
-impl<'a> TagRenderableAttrs for A<'a> {
-    fn get_attributes(&self) -> Vec<&dyn Attribute> {
-        util::full_attrs(vec![&self.href], &self.styles)
-    }
-}
-
 #[derive(
     TagRenderableName,
     GenericRenderable,
     TagRenderable,
+    TagRenderableAttrs,
+    TagRenderableGlobalAttrsSetter,
     TagRenderableStyleSetter,
     TagRenderableIntoElement,
 )]
 #[tag_renderable_name(name = "form")]
 pub struct Form<'a> {
+    #[tag_renderable_attr]
     pub formmethod: attributes::Formmethod,
+    #[tag_renderable_attr]
     pub action: Option<attributes::Action>,
     pub inputs: Vec<Input<'a>>,
     pub button: Button<'a>,
+    #[tag_renderable_global_attrs]
+    pub global_attrs: attributes::GlobalAttrs,
     #[tag_renderable_style]
     pub styles: attributes::StyleAttr<'a>,
 }
@@ -784,28 +782,18 @@ impl<'a> Form<'a> {
         button: Button<'a>,
     ) -> Self {
         Form {
-            formmethod: formmethod,
-            action: action,
-            inputs: inputs,
-            button: button,
+            formmethod,
+            action,
+            inputs,
+            button,
+            global_attrs: attributes::GlobalAttrs::empty(),
             styles: attributes::StyleAttr::empty(),
         }
     }
 }
 
-impl<'a> TagRenderableAttrs for Form<'a> {
-    fn get_attributes(&self) -> Vec<&dyn Attribute> {
-        let mut attrs: Vec<&dyn Attribute> = vec![&self.formmethod];
-        match self.action {
-            Some(ref a) => attrs.push(a),
-            None => {}
-        };
-        util::full_attrs(attrs, &self.styles)
-    }
-}
-
 impl<'a> TagRenderableChildren for Form<'a> {
-    fn get_children(&self) -> Result<Vec<Renderable>, String> {
+    fn get_children(&self) -> Result<Vec<Renderable<'_>>, RenderError> {
         let mut children: Vec<Renderable> =
             self.inputs.iter().map(|x| Renderable::Tag(x)).collect();
         children.push(Renderable::Tag(&self.button));
@@ -817,33 +805,32 @@ impl<'a> TagRenderableChildren for Form<'a> {
     TagRenderableName,
     GenericRenderable,
     TagRenderable,
+    TagRenderableAttrs,
+    TagRenderableGlobalAttrsSetter,
     TagRenderableStyleSetter,
     TagRenderableIntoElement,
 )]
 #[tag_renderable_name(name = "button")]
 pub struct Button<'a> {
-    pub child: Element,
+    pub child: Element<'a>,
+    #[tag_renderable_global_attrs]
+    pub global_attrs: attributes::GlobalAttrs,
     #[tag_renderable_style]
     pub styles: attributes::StyleAttr<'a>,
 }
 
 impl<'a> Button<'a> {
-    pub fn style_less(child: Element) -> Self {
+    pub fn style_less(child: Element<'a>) -> Self {
         Button {
-            child: child,
+            child,
+            global_attrs: attributes::GlobalAttrs::empty(),
             styles: attributes::StyleAttr::empty(),
         }
     }
 }
 
-impl<'a> TagRenderableAttrs for Button<'a> {
-    fn get_attributes(&self) -> Vec<&dyn Attribute> {
-        util::full_attrs(vec![], &self.styles)
-    }
-}
-
 impl<'a> TagRenderableChildren for Button<'a> {
-    fn get_children(&self) -> Result<Vec<Renderable>, String> {
+    fn get_children(&self) -> Result<Vec<Renderable<'_>>, RenderError> {
         Ok(vec![self.child.into_renderable()])
     }
 }
@@ -852,14 +839,21 @@ impl<'a> TagRenderableChildren for Button<'a> {
     TagRenderableName,
     GenericRenderable,
     TagRenderable,
+    TagRenderableAttrs,
+    TagRenderableGlobalAttrsSetter,
     TagRenderableStyleSetter,
     TagRenderableIntoElement,
 )]
-#[tag_renderable_name(name = "input")]
+#[tag_renderable_name(name = "input", void)]
 pub struct Input<'a> {
+    #[tag_renderable_attr]
     pub type_: Option<attributes::InputType>,
+    #[tag_renderable_attr]
     pub name: attributes::Name,
+    #[tag_renderable_attr]
     pub value: attributes::Value,
+    #[tag_renderable_global_attrs]
+    pub global_attrs: attributes::GlobalAttrs,
     #[tag_renderable_style]
     pub styles: attributes::StyleAttr<'a>,
 }
@@ -871,27 +865,244 @@ impl<'a> Input<'a> {
         value: attributes::Value,
     ) -> Self {
         Input {
-            type_: type_,
-            name: name,
-            value: value,
+            type_,
+            name,
+            value,
+            global_attrs: attributes::GlobalAttrs::empty(),
             styles: attributes::StyleAttr::empty(),
         }
     }
 }
 
-impl<'a> TagRenderableAttrs for Input<'a> {
-    fn get_attributes(&self) -> Vec<&dyn Attribute> {
-        let mut attrs: Vec<&dyn Attribute> = vec![&self.name, &self.value];
-        match self.type_ {
-            Some(ref a) => attrs.push(a),
-            None => {}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use htmldsl_internal::element_traits::{
+        fragment, text, PrettyConfig, TagRenderableIntoElement,
+    };
+
+    type Render = fn(&Renderable) -> Result<String, RenderError>;
+
+    #[test]
+    fn void_elements_have_no_end_tag() {
+        let div = Div::style_less(vec![
+            Img::style_less_with_src("a.png".into()).into_element(),
+            Input::style_less(
+                None,
+                attributes::Name {
+                    inner: units::ValueString::new("q".into()),
+                },
+                attributes::Value {
+                    inner: units::ValueString::new("".into()),
+                },
+            )
+            .into_element(),
+        ]);
+        let meta = Meta::style_less(Some(attributes::Charset {
+            value: units::CharsetValue::Utf8,
+        }));
+        let cases: [(Render, &str, &str); 4] = [
+            (
+                |r| r.try_render(),
+                "<div><img src=\"a.png\"><input name=\"q\" value=\"\"></div>",
+                "<meta charset=\"utf-8\">",
+            ),
+            (
+                |r| r.try_render_pretty(),
+                "\n<div><img src=\"a.png\"><input name=\"q\" value=\"\"></div>",
+                "\n<meta charset=\"utf-8\">",
+            ),
+            (
+                |r| r.try_render_minified(),
+                "<div><img src=a.png><input name=q value></div>",
+                "<meta charset=utf-8>",
+            ),
+            (
+                |r| r.try_render_xhtml(),
+                "<div><img src=\"a.png\"/><input name=\"q\" value=\"\"/></div>",
+                "<meta charset=\"utf-8\"/>",
+            ),
+        ];
+        for (render, expected_div, expected_meta) in cases.iter() {
+            assert_eq!(render(&Renderable::Tag(&div)).unwrap(), *expected_div);
+            assert_eq!(render(&Renderable::Tag(&meta)).unwrap(), *expected_meta);
+        }
+    }
+
+    fn page() -> Div<'static> {
+        Div::style_less(vec![
+            P::style_less(vec![
+                text("a < b"),
+                Img::style_less_with_src("a.png".into()).into_element(),
+            ])
+            .into_element(),
+            Pre::style_less(vec![text("  x\n  y")]).into_element(),
+        ])
+    }
+
+    #[test]
+    fn streamed_output_matches_the_string() {
+        let div = page();
+        let renderable = Renderable::Tag(&div);
+
+        let mut streamed = String::new();
+        renderable.try_render_to(&mut streamed).unwrap();
+        assert_eq!(streamed, renderable.try_render().unwrap());
+        let mut streamed = String::new();
+        renderable.try_render_pretty_to(&mut streamed).unwrap();
+        assert_eq!(streamed, renderable.try_render_pretty().unwrap());
+
+        let mut bytes = Vec::new();
+        renderable.render_to_io(&mut bytes).unwrap();
+        assert_eq!(bytes, renderable.try_render().unwrap().into_bytes());
+        let mut bytes = Vec::new();
+        renderable.render_pretty_to_io(&mut bytes).unwrap();
+        assert_eq!(bytes, renderable.try_render_pretty().unwrap().into_bytes());
+    }
+
+    /// Accepts `remaining` bytes, then fails every write.
+    struct FailingWriter {
+        remaining: usize,
+    }
+
+    impl std::io::Write for FailingWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if buf.len() > self.remaining {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::BrokenPipe,
+                    "closed",
+                ));
+            }
+            self.remaining -= buf.len();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl std::fmt::Write for FailingWriter {
+        fn write_str(&mut self, s: &str) -> std::fmt::Result {
+            std::io::Write::write_all(self, s.as_bytes()).map_err(|_| std::fmt::Error)
+        }
+    }
+
+    #[test]
+    fn writer_errors_are_returned() {
+        let div = page();
+        let renderable = Renderable::Tag(&div);
+        for remaining in [0, 5, 20] {
+            let error = renderable
+                .render_to_io(&mut FailingWriter { remaining })
+                .unwrap_err();
+            assert_eq!(error.kind(), std::io::ErrorKind::BrokenPipe);
+            assert!(matches!(
+                renderable.try_render_to(&mut FailingWriter { remaining }),
+                Err(RenderError::Write)
+            ));
+        }
+    }
+
+    fn link(label: &'static str) -> Element<'static> {
+        A::style_less(
+            attributes::Href {
+                value: units::SourceValue::new("/x".into()),
+            },
+            vec![text(label)],
+        )
+        .into_element()
+    }
+
+    #[test]
+    fn pretty_keeps_inline_runs_on_one_line() {
+        let p = P::style_less(vec![text("Hello "), link("x"), text(" world")]);
+        assert_eq!(
+            Renderable::Tag(&p).try_render_pretty().unwrap(),
+            "\n<p>Hello <a href=\"/x\">x</a> world</p>"
+        );
+    }
+
+    #[test]
+    fn pretty_keeps_nested_pre_verbatim() {
+        let div = Div::style_less(vec![Div::style_less(vec![Pre::style_less(vec![
+            text("  a\n\n    b  "),
+            Div::style_less(vec![text(" c ")]).into_element(),
+        ])
+        .into_element()])
+        .into_element()]);
+        assert_eq!(
+            Renderable::Tag(&div).try_render_pretty().unwrap(),
+            "\n<div>\n\t<div>\n\t\t<pre>  a\n\n    b  <div> c </div></pre>\n\t</div>\n</div>"
+        );
+    }
+
+    #[test]
+    fn pretty_wraps_at_max_width_with_the_configured_indent() {
+        let div = Div::style_less(vec![
+            P::style_less(vec![text("one "), link("two")]).into_element(),
+            P::style_less(vec![text("three")]).into_element(),
+        ]);
+        let renderable = Renderable::Tag(&div);
+        let config = |max_width| PrettyConfig {
+            indent: "  ".into(),
+            max_width,
+            ..PrettyConfig::default()
         };
-        util::full_attrs(attrs, &self.styles)
+
+        assert_eq!(
+            renderable.try_render_pretty_with(&config(None)).unwrap(),
+            "\n<div>\n  <p>one <a href=\"/x\">two</a></p>\n  <p>three</p>\n</div>"
+        );
+        // Exactly as wide as the compact rendering, so it stays on one line
+        let compact = renderable.try_render().unwrap();
+        assert_eq!(
+            renderable
+                .try_render_pretty_with(&config(Some(compact.len())))
+                .unwrap(),
+            format!("\n{}", compact)
+        );
+        assert_eq!(
+            renderable
+                .try_render_pretty_with(&config(Some(compact.len() - 1)))
+                .unwrap(),
+            "\n<div>\n  <p>one <a href=\"/x\">two</a></p>\n  <p>three</p>\n</div>"
+        );
+    }
+
+    #[test]
+    fn fragments_splice_into_their_parent() {
+        let div = Div::style_less(vec![
+            P::style_less(vec![text("a")]).into_element(),
+            fragment(vec![
+                P::style_less(vec![text("b")]).into_element(),
+                fragment(vec![P::style_less(vec![text("c")]).into_element()]),
+                fragment(vec![]),
+            ]),
+        ]);
+        let renderable = Renderable::Tag(&div);
+        assert_eq!(
+            renderable.try_render().unwrap(),
+            "<div><p>a</p><p>b</p><p>c</p></div>"
+        );
+        assert_eq!(
+            renderable.try_render_pretty().unwrap(),
+            "\n<div>\n\t<p>a</p>\n\t<p>b</p>\n\t<p>c</p>\n</div>"
+        );
+        // The fragments are flattened first, so only the final `p` counts as the last child
+        assert_eq!(
+            renderable.try_render_minified().unwrap(),
+            "<div><p>a<p>b<p>c</div>"
+        );
     }
-}
 
-impl<'a> TagRenderableChildren for Input<'a> {
-    fn get_children(&self) -> Result<Vec<Renderable>, String> {
-        Ok(Vec::new())
+    #[test]
+    fn empty_fragment_renders_nothing() {
+        let empty = fragment(vec![fragment(vec![])]);
+        let renderable = empty.into_renderable();
+        assert_eq!(renderable.try_render().unwrap(), "");
+        assert_eq!(renderable.try_render_pretty().unwrap(), "");
+        assert_eq!(renderable.try_render_minified().unwrap(), "");
+        assert_eq!(renderable.try_render_xhtml().unwrap(), "");
     }
 }
@@ -1,21 +1,51 @@
 pub mod attributes;
+pub mod document;
 pub mod elements;
 pub mod style_sheet;
 pub mod styles;
 pub mod units;
 
+use std::fmt;
+use std::io;
+
 pub use htmldsl_internal::element_traits::*;
 
 #[macro_use]
 extern crate htmldsl_internal_derive;
 
+/// Leaves out whatever can not be rendered, see `try_render_simple_html_page` for the errors.
 pub fn render_simple_html_page(pretty: bool, html: elements::Html) -> String {
-    let tag = htmldsl_internal::element_traits::Renderable::Tag(&html);
-    format!(
-        "<!DOCTYPE html>{}",
-        match pretty {
-            true => tag.render_pretty(),
-            false => tag.render(),
-        }
-    )
+    document::Document::new(html).render(pretty)
+}
+
+pub fn try_render_simple_html_page(
+    pretty: bool,
+    html: elements::Html,
+) -> Result<String, RenderError> {
+    document::Document::new(html).try_render(pretty)
+}
+
+/// Leaves out whatever can not be rendered, see `try_render_simple_html_page_to` for the errors.
+pub fn render_simple_html_page_to<W: fmt::Write>(
+    pretty: bool,
+    html: elements::Html,
+    w: &mut W,
+) -> fmt::Result {
+    document::Document::new(html).render_to(pretty, w)
+}
+
+pub fn try_render_simple_html_page_to<W: fmt::Write>(
+    pretty: bool,
+    html: elements::Html,
+    w: &mut W,
+) -> Result<(), RenderError> {
+    document::Document::new(html).try_render_to(pretty, w)
+}
+
+pub fn render_simple_html_page_to_io<W: io::Write>(
+    pretty: bool,
+    html: elements::Html,
+    w: &mut W,
+) -> io::Result<()> {
+    document::Document::new(html).render_to_io(pretty, w)
 }
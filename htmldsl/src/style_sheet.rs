@@ -6,7 +6,7 @@ pub struct StyleSheet<'a> {
 
 pub struct StyleAssignment<'a> {
     pub names: Vec<String>,
-    pub styles: Vec<&'a dyn style_traits::Style>,
+    pub styles: Vec<Box<dyn style_traits::Style + 'a>>,
 }
 
 pub fn style_sheet_string(style_sheet: &StyleSheet) -> String {
@@ -1,11 +1,56 @@
 use super::units;
 use htmldsl_internal::style_traits::Style;
 
+/// Marks the wrapped style as `!important`.
+pub struct Important<S: Style>(pub S);
+
+impl<S: Style> Style for Important<S> {
+    fn style_key(&self) -> String {
+        self.0.style_key()
+    }
+
+    fn style_value(&self) -> String {
+        self.0.style_value()
+    }
+
+    fn is_important(&self) -> bool {
+        true
+    }
+
+    fn is_fallback(&self) -> bool {
+        self.0.is_fallback()
+    }
+}
+
+/// Keeps the wrapped style even when a later style sets the same property.
+pub struct Fallback<S: Style>(pub S);
+
+impl<S: Style> Style for Fallback<S> {
+    fn style_key(&self) -> String {
+        self.0.style_key()
+    }
+
+    fn style_value(&self) -> String {
+        self.0.style_value()
+    }
+
+    fn is_important(&self) -> bool {
+        self.0.is_important()
+    }
+
+    fn is_fallback(&self) -> bool {
+        true
+    }
+}
+
 pub enum Display {
     Inline,
     InlineBlock,
     Block,
+    Flex,
+    InlineFlex,
     Grid,
+    InlineGrid,
     None,
 }
 
@@ -19,7 +64,10 @@ impl Style for Display {
             Display::Inline => "inline".into(),
             Display::InlineBlock => "inline-block".into(),
             Display::Block => "block".into(),
+            Display::Flex => "flex".into(),
+            Display::InlineFlex => "inline-flex".into(),
             Display::Grid => "grid".into(),
+            Display::InlineGrid => "inline-grid".into(),
             Display::None => "none".into(),
         }
     }
@@ -161,7 +209,7 @@ impl Style for VerticalAlign {
 }
 
 pub struct MaxWidth {
-    pub value: u32,
+    pub value: units::Number,
 }
 
 impl Style for MaxWidth {
@@ -170,12 +218,12 @@ impl Style for MaxWidth {
     }
 
     fn style_value(&self) -> String {
-        format!("{}px", self.value)
+        self.value.style_value_helper()
     }
 }
 
 pub struct BackgroundColor {
-    pub color_value: &'static str,
+    pub value: units::ColorValue,
 }
 
 impl Style for BackgroundColor {
@@ -184,12 +232,12 @@ impl Style for BackgroundColor {
     }
 
     fn style_value(&self) -> String {
-        self.color_value.into()
+        self.value.value_string()
     }
 }
 
 pub struct Color {
-    pub color_value: &'static str,
+    pub value: units::ColorValue,
 }
 
 impl Style for Color {
@@ -198,12 +246,24 @@ impl Style for Color {
     }
 
     fn style_value(&self) -> String {
-        self.color_value.to_string()
+        self.value.value_string()
     }
 }
 
+/// A list of font family names, tried in order before falling back to the generic family.
+/// Names are quoted whenever they would not read as a plain list of identifiers.
 pub struct FontFamily {
-    pub name: &'static str,
+    pub families: Vec<String>,
+    pub generic: units::GenericFontFamily,
+}
+
+impl FontFamily {
+    pub fn new<S: Into<String>>(families: Vec<S>, generic: units::GenericFontFamily) -> Self {
+        FontFamily {
+            families: families.into_iter().map(|f| f.into()).collect(),
+            generic,
+        }
+    }
 }
 
 impl Style for FontFamily {
@@ -211,8 +271,72 @@ impl Style for FontFamily {
         "font-family".into()
     }
 
+    // An empty name can not be written as a family, so it is left out
     fn style_value(&self) -> String {
-        self.name.to_string()
+        self.families
+            .iter()
+            .filter(|f| !f.trim().is_empty())
+            .map(|f| quote_font_family(f))
+            .chain(std::iter::once(self.generic.value_string()))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+// Keywords which would change meaning if a family name was left unquoted
+const RESERVED_FONT_FAMILY_WORDS: &[&str] = &[
+    "serif",
+    "sans-serif",
+    "monospace",
+    "cursive",
+    "fantasy",
+    "system-ui",
+    "ui-serif",
+    "ui-sans-serif",
+    "ui-monospace",
+    "ui-rounded",
+    "math",
+    "emoji",
+    "fangsong",
+    "inherit",
+    "initial",
+    "unset",
+    "revert",
+    "revert-layer",
+    "default",
+];
+
+fn quote_font_family(family: &str) -> String {
+    let is_ident = |w: &str| {
+        let rest = w.strip_prefix('-').unwrap_or(w);
+        !rest.is_empty()
+            && !rest.starts_with(|c: char| c.is_ascii_digit() || c == '-')
+            && w.chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || !c.is_ascii())
+    };
+    let words: Vec<&str> = family.split(' ').collect();
+    let plain = words.iter().all(|w| is_ident(w))
+        && !(words.len() == 1
+            && RESERVED_FONT_FAMILY_WORDS.contains(&words[0].to_ascii_lowercase().as_str()));
+    match plain {
+        true => family.to_string(),
+        false => {
+            let mut quoted = String::from("\"");
+            for c in family.chars() {
+                match c {
+                    '"' | '\\' => {
+                        quoted.push('\\');
+                        quoted.push(c);
+                    }
+                    '\n' => quoted.push_str("\\a "),
+                    '\r' => quoted.push_str("\\d "),
+                    '\u{c}' => quoted.push_str("\\c "),
+                    c => quoted.push(c),
+                }
+            }
+            quoted.push('"');
+            quoted
+        }
     }
 }
 
@@ -310,3 +434,878 @@ impl Style for Left {
         self.value.style_value_helper()
     }
 }
+
+pub struct FlexDirection {
+    pub value: units::FlexDirectionValue,
+}
+
+impl Style for FlexDirection {
+    fn style_key(&self) -> String {
+        "flex-direction".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.value_string()
+    }
+}
+
+pub struct FlexWrap {
+    pub value: units::FlexWrapValue,
+}
+
+impl Style for FlexWrap {
+    fn style_key(&self) -> String {
+        "flex-wrap".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.value_string()
+    }
+}
+
+pub struct FlexFlow {
+    pub direction: units::FlexDirectionValue,
+    pub wrap: units::FlexWrapValue,
+}
+
+impl Style for FlexFlow {
+    fn style_key(&self) -> String {
+        "flex-flow".into()
+    }
+
+    fn style_value(&self) -> String {
+        format!(
+            "{} {}",
+            self.direction.value_string(),
+            self.wrap.value_string()
+        )
+    }
+}
+
+pub struct JustifyContent {
+    pub value: units::JustifyContentValue,
+}
+
+impl Style for JustifyContent {
+    fn style_key(&self) -> String {
+        "justify-content".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.value_string()
+    }
+}
+
+pub struct AlignItems {
+    pub value: units::AlignItemsValue,
+}
+
+impl Style for AlignItems {
+    fn style_key(&self) -> String {
+        "align-items".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.value_string()
+    }
+}
+
+pub struct AlignContent {
+    pub value: units::AlignContentValue,
+}
+
+impl Style for AlignContent {
+    fn style_key(&self) -> String {
+        "align-content".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.value_string()
+    }
+}
+
+pub struct AlignSelf {
+    pub value: units::AlignSelfValue,
+}
+
+impl Style for AlignSelf {
+    fn style_key(&self) -> String {
+        "align-self".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.value_string()
+    }
+}
+
+pub struct Order {
+    pub value: i32,
+}
+
+impl Style for Order {
+    fn style_key(&self) -> String {
+        "order".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.to_string()
+    }
+}
+
+pub enum Flex {
+    None,
+    Auto,
+    Grow(units::FlexFactor),
+    GrowShrinkBasis(units::FlexFactor, units::FlexFactor, units::FlexBasisValue),
+}
+
+impl Style for Flex {
+    fn style_key(&self) -> String {
+        "flex".into()
+    }
+
+    fn style_value(&self) -> String {
+        match self {
+            Flex::None => "none".into(),
+            Flex::Auto => units::Auto {}.unit_str(),
+            Flex::Grow(g) => g.value_string(),
+            Flex::GrowShrinkBasis(g, s, b) => format!(
+                "{} {} {}",
+                g.value_string(),
+                s.value_string(),
+                b.style_value_helper()
+            ),
+        }
+    }
+}
+
+pub struct FlexGrow {
+    pub value: units::FlexFactor,
+}
+
+impl Style for FlexGrow {
+    fn style_key(&self) -> String {
+        "flex-grow".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.value_string()
+    }
+}
+
+pub struct FlexShrink {
+    pub value: units::FlexFactor,
+}
+
+impl Style for FlexShrink {
+    fn style_key(&self) -> String {
+        "flex-shrink".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.value_string()
+    }
+}
+
+pub struct FlexBasis {
+    pub value: units::FlexBasisValue,
+}
+
+impl Style for FlexBasis {
+    fn style_key(&self) -> String {
+        "flex-basis".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.style_value_helper()
+    }
+}
+
+pub enum Gap {
+    Both(units::Number),
+    RowColumn(units::Number, units::Number),
+}
+
+impl Style for Gap {
+    fn style_key(&self) -> String {
+        "gap".into()
+    }
+
+    fn style_value(&self) -> String {
+        match self {
+            Gap::Both(v) => v.style_value_helper(),
+            Gap::RowColumn(r, c) => {
+                format!("{} {}", r.style_value_helper(), c.style_value_helper())
+            }
+        }
+    }
+}
+
+pub struct RowGap {
+    pub value: units::Number,
+}
+
+impl Style for RowGap {
+    fn style_key(&self) -> String {
+        "row-gap".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.style_value_helper()
+    }
+}
+
+pub struct ColumnGap {
+    pub value: units::Number,
+}
+
+impl Style for ColumnGap {
+    fn style_key(&self) -> String {
+        "column-gap".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.style_value_helper()
+    }
+}
+
+pub enum GridTemplateColumns {
+    None,
+    Tracks(Vec<units::TrackListItem>),
+}
+
+impl Style for GridTemplateColumns {
+    fn style_key(&self) -> String {
+        "grid-template-columns".into()
+    }
+
+    fn style_value(&self) -> String {
+        match self {
+            GridTemplateColumns::None => "none".into(),
+            GridTemplateColumns::Tracks(items) => units::track_list_string(items),
+        }
+    }
+}
+
+pub enum GridTemplateRows {
+    None,
+    Tracks(Vec<units::TrackListItem>),
+}
+
+impl Style for GridTemplateRows {
+    fn style_key(&self) -> String {
+        "grid-template-rows".into()
+    }
+
+    fn style_value(&self) -> String {
+        match self {
+            GridTemplateRows::None => "none".into(),
+            GridTemplateRows::Tracks(items) => units::track_list_string(items),
+        }
+    }
+}
+
+pub enum GridTemplateAreas {
+    None,
+    Areas(units::GridTemplateAreas),
+}
+
+impl Style for GridTemplateAreas {
+    fn style_key(&self) -> String {
+        "grid-template-areas".into()
+    }
+
+    fn style_value(&self) -> String {
+        match self {
+            GridTemplateAreas::None => "none".into(),
+            GridTemplateAreas::Areas(areas) => areas.style_value_helper(),
+        }
+    }
+}
+
+pub enum GridArea {
+    Named(units::GridIdent),
+    Lines(
+        units::GridLineValue,
+        units::GridLineValue,
+        units::GridLineValue,
+        units::GridLineValue,
+    ),
+}
+
+impl Style for GridArea {
+    fn style_key(&self) -> String {
+        "grid-area".into()
+    }
+
+    fn style_value(&self) -> String {
+        match self {
+            GridArea::Named(n) => n.as_str().into(),
+            GridArea::Lines(row_start, column_start, row_end, column_end) => format!(
+                "{} / {} / {} / {}",
+                row_start.value_string(),
+                column_start.value_string(),
+                row_end.value_string(),
+                column_end.value_string()
+            ),
+        }
+    }
+}
+
+pub struct GridColumn {
+    pub start: units::GridLineValue,
+    pub end: Option<units::GridLineValue>,
+}
+
+impl Style for GridColumn {
+    fn style_key(&self) -> String {
+        "grid-column".into()
+    }
+
+    fn style_value(&self) -> String {
+        match self.end {
+            Some(ref end) => format!("{} / {}", self.start.value_string(), end.value_string()),
+            None => self.start.value_string(),
+        }
+    }
+}
+
+pub struct GridRow {
+    pub start: units::GridLineValue,
+    pub end: Option<units::GridLineValue>,
+}
+
+impl Style for GridRow {
+    fn style_key(&self) -> String {
+        "grid-row".into()
+    }
+
+    fn style_value(&self) -> String {
+        match self.end {
+            Some(ref end) => format!("{} / {}", self.start.value_string(), end.value_string()),
+            None => self.start.value_string(),
+        }
+    }
+}
+
+pub struct GridAutoFlow {
+    pub value: units::GridAutoFlowValue,
+}
+
+impl Style for GridAutoFlow {
+    fn style_key(&self) -> String {
+        "grid-auto-flow".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.value_string()
+    }
+}
+
+pub struct JustifyItems {
+    pub value: units::JustifyItemsValue,
+}
+
+impl Style for JustifyItems {
+    fn style_key(&self) -> String {
+        "justify-items".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.value_string()
+    }
+}
+
+pub struct JustifySelf {
+    pub value: units::JustifySelfValue,
+}
+
+impl Style for JustifySelf {
+    fn style_key(&self) -> String {
+        "justify-self".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.value_string()
+    }
+}
+
+pub struct PlaceContent {
+    pub align: units::AlignContentValue,
+    pub justify: units::JustifyContentValue,
+}
+
+impl Style for PlaceContent {
+    fn style_key(&self) -> String {
+        "place-content".into()
+    }
+
+    fn style_value(&self) -> String {
+        format!(
+            "{} {}",
+            self.align.value_string(),
+            self.justify.value_string()
+        )
+    }
+}
+
+pub struct PlaceItems {
+    pub align: units::AlignItemsValue,
+    pub justify: units::JustifyItemsValue,
+}
+
+impl Style for PlaceItems {
+    fn style_key(&self) -> String {
+        "place-items".into()
+    }
+
+    fn style_value(&self) -> String {
+        format!(
+            "{} {}",
+            self.align.value_string(),
+            self.justify.value_string()
+        )
+    }
+}
+
+pub struct PlaceSelf {
+    pub align: units::AlignSelfValue,
+    pub justify: units::JustifySelfValue,
+}
+
+impl Style for PlaceSelf {
+    fn style_key(&self) -> String {
+        "place-self".into()
+    }
+
+    fn style_value(&self) -> String {
+        format!(
+            "{} {}",
+            self.align.value_string(),
+            self.justify.value_string()
+        )
+    }
+}
+
+pub struct FontSize {
+    pub value: units::FontSizeValue,
+}
+
+impl Style for FontSize {
+    fn style_key(&self) -> String {
+        "font-size".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.style_value_helper()
+    }
+}
+
+pub struct FontWeight {
+    pub value: units::FontWeightValue,
+}
+
+impl Style for FontWeight {
+    fn style_key(&self) -> String {
+        "font-weight".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.value_string()
+    }
+}
+
+pub struct FontStyle {
+    pub value: units::FontStyleValue,
+}
+
+impl Style for FontStyle {
+    fn style_key(&self) -> String {
+        "font-style".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.value_string()
+    }
+}
+
+pub struct LineHeight {
+    pub value: units::LineHeightValue,
+}
+
+impl Style for LineHeight {
+    fn style_key(&self) -> String {
+        "line-height".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.style_value_helper()
+    }
+}
+
+pub struct LetterSpacing {
+    pub value: units::SpacingValue,
+}
+
+impl Style for LetterSpacing {
+    fn style_key(&self) -> String {
+        "letter-spacing".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.style_value_helper()
+    }
+}
+
+pub struct TextAlign {
+    pub value: units::TextAlignValue,
+}
+
+impl Style for TextAlign {
+    fn style_key(&self) -> String {
+        "text-align".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.value_string()
+    }
+}
+
+/// The `text-decoration` shorthand, unset parts are left to their initial values.
+pub struct TextDecoration {
+    pub lines: Vec<units::TextDecorationLineValue>,
+    pub style: Option<units::TextDecorationStyleValue>,
+    pub color: Option<units::ColorValue>,
+    pub thickness: Option<units::NumberOrAuto>,
+}
+
+impl Style for TextDecoration {
+    fn style_key(&self) -> String {
+        "text-decoration".into()
+    }
+
+    fn style_value(&self) -> String {
+        let mut parts = vec![text_decoration_lines_string(&self.lines)];
+        if let Some(ref style) = self.style {
+            parts.push(style.value_string());
+        }
+        if let Some(ref color) = self.color {
+            parts.push(color.value_string());
+        }
+        if let Some(ref thickness) = self.thickness {
+            parts.push(thickness.style_value_helper());
+        }
+        parts.join(" ")
+    }
+}
+
+fn text_decoration_lines_string(lines: &[units::TextDecorationLineValue]) -> String {
+    match lines.is_empty() {
+        true => "none".into(),
+        false => lines
+            .iter()
+            .map(|l| l.value_string())
+            .collect::<Vec<String>>()
+            .join(" "),
+    }
+}
+
+/// An empty list of lines renders as `none`.
+pub struct TextDecorationLine {
+    pub lines: Vec<units::TextDecorationLineValue>,
+}
+
+impl Style for TextDecorationLine {
+    fn style_key(&self) -> String {
+        "text-decoration-line".into()
+    }
+
+    fn style_value(&self) -> String {
+        text_decoration_lines_string(&self.lines)
+    }
+}
+
+pub struct TextDecorationStyle {
+    pub value: units::TextDecorationStyleValue,
+}
+
+impl Style for TextDecorationStyle {
+    fn style_key(&self) -> String {
+        "text-decoration-style".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.value_string()
+    }
+}
+
+pub struct TextDecorationColor {
+    pub value: units::ColorValue,
+}
+
+impl Style for TextDecorationColor {
+    fn style_key(&self) -> String {
+        "text-decoration-color".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.value_string()
+    }
+}
+
+pub struct TextDecorationThickness {
+    pub value: units::NumberOrAuto,
+}
+
+impl Style for TextDecorationThickness {
+    fn style_key(&self) -> String {
+        "text-decoration-thickness".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.style_value_helper()
+    }
+}
+
+pub struct TextTransform {
+    pub value: units::TextTransformValue,
+}
+
+impl Style for TextTransform {
+    fn style_key(&self) -> String {
+        "text-transform".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.value_string()
+    }
+}
+
+pub struct WhiteSpace {
+    pub value: units::WhiteSpaceValue,
+}
+
+impl Style for WhiteSpace {
+    fn style_key(&self) -> String {
+        "white-space".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.value_string()
+    }
+}
+
+pub struct WordBreak {
+    pub value: units::WordBreakValue,
+}
+
+impl Style for WordBreak {
+    fn style_key(&self) -> String {
+        "word-break".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.value_string()
+    }
+}
+
+pub struct OverflowWrap {
+    pub value: units::OverflowWrapValue,
+}
+
+impl Style for OverflowWrap {
+    fn style_key(&self) -> String {
+        "overflow-wrap".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.value_string()
+    }
+}
+
+pub struct TextOverflow {
+    pub value: units::TextOverflowValue,
+}
+
+impl Style for TextOverflow {
+    fn style_key(&self) -> String {
+        "text-overflow".into()
+    }
+
+    fn style_value(&self) -> String {
+        self.value.value_string()
+    }
+}
+
+/// Each group is optional, with nothing set it renders as `normal`.
+pub struct FontVariantNumeric {
+    pub figure: Option<units::NumericFigureValue>,
+    pub spacing: Option<units::NumericSpacingValue>,
+    pub fraction: Option<units::NumericFractionValue>,
+    pub ordinal: bool,
+    pub slashed_zero: bool,
+}
+
+impl Style for FontVariantNumeric {
+    fn style_key(&self) -> String {
+        "font-variant-numeric".into()
+    }
+
+    fn style_value(&self) -> String {
+        let mut parts: Vec<String> = Vec::new();
+        if let Some(ref v) = self.figure {
+            parts.push(v.value_string());
+        }
+        if let Some(ref v) = self.spacing {
+            parts.push(v.value_string());
+        }
+        if let Some(ref v) = self.fraction {
+            parts.push(v.value_string());
+        }
+        if self.ordinal {
+            parts.push("ordinal".into());
+        }
+        if self.slashed_zero {
+            parts.push("slashed-zero".into());
+        }
+        match parts.is_empty() {
+            true => "normal".into(),
+            false => parts.join(" "),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn font_families_are_quoted_where_needed() {
+        let cases = [
+            (
+                vec!["Helvetica Neue", "Arial"],
+                "Helvetica Neue, Arial, sans-serif",
+            ),
+            (vec!["serif"], "\"serif\", sans-serif"),
+            (vec!["Font 3"], "\"Font 3\", sans-serif"),
+            (vec!["a\"b\\c"], "\"a\\\"b\\\\c\", sans-serif"),
+            (vec!["a\nb\rc\u{c}d"], "\"a\\a b\\d c\\c d\", sans-serif"),
+            (vec!["", "  ", "Arial"], "Arial, sans-serif"),
+            (vec![""], "sans-serif"),
+        ];
+        for (families, expected) in cases.iter() {
+            let family = FontFamily::new(families.clone(), units::GenericFontFamily::SansSerif);
+            assert_eq!(family.style_value(), *expected);
+        }
+    }
+
+    fn declaration(style: &dyn Style) -> String {
+        format!("{}: {}", style.style_key(), style.style_value())
+    }
+
+    #[test]
+    fn flex_styles_are_rendered() {
+        let half = units::Finite::new(0.5).unwrap();
+        let cases: Vec<(Box<dyn Style>, &str)> = vec![
+            (Box::new(Display::InlineFlex), "display: inline-flex"),
+            (
+                Box::new(FlexFlow {
+                    direction: units::FlexDirectionValue::ColumnReverse,
+                    wrap: units::FlexWrapValue::Wrap,
+                }),
+                "flex-flow: column-reverse wrap",
+            ),
+            (Box::new(Flex::Auto), "flex: auto"),
+            (Box::new(Flex::Grow(2.into())), "flex: 2"),
+            (
+                Box::new(Flex::GrowShrinkBasis(
+                    1.into(),
+                    units::FlexFactor::new(half).unwrap(),
+                    units::px(120).into(),
+                )),
+                "flex: 1 0.5 120px",
+            ),
+            (
+                Box::new(FlexGrow {
+                    value: units::FlexFactor::new(half).unwrap(),
+                }),
+                "flex-grow: 0.5",
+            ),
+            (Box::new(FlexShrink { value: 0.into() }), "flex-shrink: 0"),
+            (
+                Box::new(FlexBasis {
+                    value: units::FlexBasisValue::Content,
+                }),
+                "flex-basis: content",
+            ),
+            (Box::new(Order { value: -1 }), "order: -1"),
+            (
+                Box::new(Gap::RowColumn(units::rem(1), units::percent(5))),
+                "gap: 1rem 5%",
+            ),
+        ];
+        for (style, expected) in cases.iter() {
+            assert_eq!(declaration(style.as_ref()), *expected);
+        }
+    }
+
+    #[test]
+    fn flex_factors_can_not_be_negative() {
+        assert!(units::FlexFactor::new(-1).is_err());
+        assert!(units::FlexFactor::new(units::Finite::new(-0.5).unwrap()).is_err());
+        assert!(units::FlexFactor::new(0).is_ok());
+    }
+
+    #[test]
+    fn grid_styles_are_rendered() {
+        let ident = |name| units::GridIdent::new(name).unwrap();
+        let cases: Vec<(Box<dyn Style>, &str)> = vec![
+            (
+                Box::new(GridTemplateColumns::Tracks(vec![
+                    units::TrackListItem::LineNames(vec![ident("start")]),
+                    units::TrackListItem::Size(units::px(200).into()),
+                    units::TrackListItem::Repeat(
+                        units::RepeatCount::AutoFill,
+                        vec![units::TrackListItem::Size(units::TrackSize::minmax(
+                            units::px(100),
+                            units::fr(1),
+                        ))],
+                    ),
+                ])),
+                "grid-template-columns: [start] 200px repeat(auto-fill, minmax(100px, 1fr))",
+            ),
+            (Box::new(GridTemplateRows::None), "grid-template-rows: none"),
+            (
+                Box::new(GridTemplateAreas::Areas(
+                    units::GridTemplateAreas::new(vec![vec!["head", "head"], vec!["side", "main"]])
+                        .unwrap(),
+                )),
+                "grid-template-areas: \"head head\" \"side main\"",
+            ),
+            (Box::new(GridArea::Named(ident("main"))), "grid-area: main"),
+            (
+                Box::new(GridColumn {
+                    start: units::GridLineValue::Named(ident("start")),
+                    end: Some(units::GridLineValue::SpanNamed(ident("end"))),
+                }),
+                "grid-column: start / span end",
+            ),
+            (
+                Box::new(GridRow {
+                    start: units::GridLineValue::Auto,
+                    end: None,
+                }),
+                "grid-row: auto",
+            ),
+            (
+                Box::new(GridAutoFlow {
+                    value: units::GridAutoFlowValue::ColumnDense,
+                }),
+                "grid-auto-flow: column dense",
+            ),
+        ];
+        for (style, expected) in cases.iter() {
+            assert_eq!(declaration(style.as_ref()), *expected);
+        }
+    }
+}
@@ -1,27 +1,219 @@
-pub enum LanguageTag {
-    En,
+/// A BCP 47 language tag, like `fr`, `sr-Latn` or `zh-Hant-TW`. Subtags are stored in their
+/// canonical case. The grandfathered tags of the registry (like `i-klingon` or `en-GB-oed`),
+/// which predate the current grammar, are kept whole and have no language or other subtags.
+pub struct LanguageTag {
+    language: String,
+    extlangs: Vec<String>,
+    script: Option<String>,
+    region: Option<String>,
+    variants: Vec<String>,
+    extensions: Vec<String>,
+    private_use: Vec<String>,
+    grandfathered: Option<&'static str>,
 }
 
 impl LanguageTag {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let err = |reason: &str| Err(format!("invalid language tag {:?}: {}", s, reason));
+        if let Some(grandfathered) = GRANDFATHERED_TAGS
+            .iter()
+            .find(|t| t.eq_ignore_ascii_case(s))
+        {
+            return Ok(LanguageTag {
+                grandfathered: Some(grandfathered),
+                ..LanguageTag::empty()
+            });
+        }
+
+        let subtags: Vec<&str> = s.split('-').collect();
+        if subtags
+            .iter()
+            .any(|t| t.is_empty() || t.len() > 8 || !t.chars().all(|c| c.is_ascii_alphanumeric()))
+        {
+            return err("subtags must be 1 to 8 letters or digits");
+        }
+
+        let mut tag = LanguageTag::empty();
+        let mut rest = subtags.iter().map(|t| t.to_ascii_lowercase()).peekable();
+
+        // A tag made only of private use subtags has no language
+        if rest.peek().map(|t| t.as_str()) != Some("x") {
+            match rest.next() {
+                Some(ref t) if (2..=8).contains(&t.len()) && t.len() != 4 && is_alpha(t) => {
+                    tag.language = t.clone()
+                }
+                _ => return err("the language must be 2, 3 or 5 to 8 letters"),
+            }
+            while tag.language.len() <= 3 && tag.extlangs.len() < 3 {
+                match rest.next_if(|t| t.len() == 3 && is_alpha(t)) {
+                    Some(t) => tag.extlangs.push(t),
+                    None => break,
+                }
+            }
+            tag.script = rest
+                .next_if(|t| t.len() == 4 && is_alpha(t))
+                .map(|t| t[..1].to_ascii_uppercase() + &t[1..]);
+            tag.region = rest
+                .next_if(|t| (t.len() == 2 && is_alpha(t)) || (t.len() == 3 && is_digit(t)))
+                .map(|t| t.to_ascii_uppercase());
+            while let Some(t) = rest.next_if(|t| {
+                t.len() >= 5 || (t.len() == 4 && t.starts_with(|c: char| c.is_ascii_digit()))
+            }) {
+                if tag.variants.contains(&t) {
+                    return err("variants must not repeat");
+                }
+                tag.variants.push(t);
+            }
+            while let Some(singleton) = rest.next_if(|t| t.len() == 1 && t != "x") {
+                if tag.extensions.iter().any(|e| e.starts_with(&singleton)) {
+                    return err("extensions must not repeat");
+                }
+                let mut extension = singleton;
+                while let Some(t) = rest.next_if(|t| t.len() >= 2) {
+                    extension = extension + "-" + &t;
+                }
+                if extension.len() == 1 {
+                    return err("extensions must have at least one subtag");
+                }
+                tag.extensions.push(extension);
+            }
+        }
+
+        if rest.next_if(|t| t == "x").is_some() {
+            tag.private_use = rest.by_ref().collect();
+            if tag.private_use.is_empty() {
+                return err("private use must have at least one subtag");
+            }
+        }
+        match rest.next() {
+            Some(t) => err(&format!("unexpected subtag {:?}", t)),
+            None => Ok(tag),
+        }
+    }
+
+    fn empty() -> Self {
+        LanguageTag {
+            language: String::new(),
+            extlangs: Vec::new(),
+            script: None,
+            region: None,
+            variants: Vec::new(),
+            extensions: Vec::new(),
+            private_use: Vec::new(),
+            grandfathered: None,
+        }
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    pub fn script(&self) -> Option<&str> {
+        self.script.as_deref()
+    }
+
+    pub fn region(&self) -> Option<&str> {
+        self.region.as_deref()
+    }
+
+    pub fn variants(&self) -> &[String] {
+        &self.variants
+    }
+
+    /// The text direction of the tag's script, or of the language's usual script when no
+    /// script is given.
+    pub fn direction(&self) -> DirValue {
+        let rtl = match self.script {
+            Some(ref script) => RTL_SCRIPTS.contains(&script.as_str()),
+            None => RTL_LANGUAGES.contains(&self.language.as_str()),
+        };
+        match rtl {
+            true => DirValue::Rtl,
+            false => DirValue::Ltr,
+        }
+    }
+
+    pub fn is_grandfathered(&self) -> bool {
+        self.grandfathered.is_some()
+    }
+
     pub fn lang_tag_str(&self) -> String {
-        match self {
-            LanguageTag::En => "en".into(),
+        if let Some(grandfathered) = self.grandfathered {
+            return grandfathered.to_string();
         }
+        let mut parts: Vec<&str> = Vec::new();
+        if !self.language.is_empty() {
+            parts.push(&self.language);
+        }
+        parts.extend(self.extlangs.iter().map(|t| t.as_str()));
+        parts.extend(self.script.as_deref());
+        parts.extend(self.region.as_deref());
+        parts.extend(self.variants.iter().map(|t| t.as_str()));
+        parts.extend(self.extensions.iter().map(|t| t.as_str()));
+        if !self.private_use.is_empty() {
+            parts.push("x");
+            parts.extend(self.private_use.iter().map(|t| t.as_str()));
+        }
+        parts.join("-")
     }
 }
 
-pub enum LanguageSubTag {
-    Us,
-}
+impl std::str::FromStr for LanguageTag {
+    type Err = String;
 
-impl LanguageSubTag {
-    pub fn sub_tag_str(&self) -> String {
-        match self {
-            LanguageSubTag::Us => "US".into(),
-        }
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LanguageTag::parse(s)
     }
 }
 
+fn is_alpha(s: &str) -> bool {
+    s.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+fn is_digit(s: &str) -> bool {
+    s.chars().all(|c| c.is_ascii_digit())
+}
+
+// Both the irregular tags, which do not match the grammar, and the regular ones, whose subtags
+// do not mean what the grammar would read them as
+const GRANDFATHERED_TAGS: &[&str] = &[
+    "en-GB-oed",
+    "i-ami",
+    "i-bnn",
+    "i-default",
+    "i-enochian",
+    "i-hak",
+    "i-klingon",
+    "i-lux",
+    "i-mingo",
+    "i-navajo",
+    "i-pwn",
+    "i-tao",
+    "i-tay",
+    "i-tsu",
+    "sgn-BE-FR",
+    "sgn-BE-NL",
+    "sgn-CH-DE",
+    "art-lojban",
+    "cel-gaulish",
+    "no-bok",
+    "no-nyn",
+    "zh-guoyu",
+    "zh-hakka",
+    "zh-min",
+    "zh-min-nan",
+    "zh-xiang",
+];
+
+const RTL_SCRIPTS: &[&str] = &[
+    "Adlm", "Arab", "Hebr", "Mand", "Mend", "Nkoo", "Rohg", "Samr", "Syrc", "Thaa", "Yezi",
+];
+
+const RTL_LANGUAGES: &[&str] = &[
+    "ar", "arc", "ckb", "dv", "fa", "he", "iw", "ks", "ku", "nqo", "ps", "sd", "syr", "ug", "ur",
+    "yi",
+];
+
 pub enum CharsetValue {
     Utf8,
 }
@@ -34,18 +226,324 @@ impl CharsetValue {
     }
 }
 
+/// A url for `src`, `href` and `action` attributes.
+///
+/// `new` takes the string as is, without any checks, for urls which are fully trusted. The
+/// checked constructors, `try_new` and `from_url`, reject the schemes which can run script
+/// (`javascript:`, `vbscript:` and `data:`), use a `SourceCheck` to allow them.
 pub struct SourceValue {
-    inner_string: String,
+    inner: SourceInner,
+}
+
+enum SourceInner {
+    Raw(String),
+    Url(Url),
 }
 
+const DANGEROUS_SCHEMES: &[&str] = &["javascript", "vbscript", "data"];
+
 impl SourceValue {
     pub fn new(s: String) -> Self {
-        SourceValue { inner_string: s }
+        SourceValue {
+            inner: SourceInner::Raw(s),
+        }
+    }
+
+    pub fn try_new(s: String) -> Result<Self, String> {
+        SourceCheck::new().try_new(s)
+    }
+
+    pub fn from_url(url: Url) -> Result<Self, String> {
+        SourceCheck::new().from_url(url)
     }
 
     pub fn source_value_str(&self) -> String {
-        self.inner_string.clone()
+        match self.inner {
+            SourceInner::Raw(ref s) => s.clone(),
+            SourceInner::Url(ref u) => u.url_string(),
+        }
+    }
+
+    /// The lowercased scheme as a browser would read it, if the value has one.
+    pub fn scheme(&self) -> Option<String> {
+        // Browsers ignore leading control characters and spaces and any tabs or newlines
+        let cleaned: String = self
+            .source_value_str()
+            .trim_start_matches(|c: char| c <= ' ')
+            .chars()
+            .filter(|c| !matches!(c, '\t' | '\n' | '\r'))
+            .collect();
+        let end = cleaned.find([':', '/', '?', '#'])?;
+        let scheme = &cleaned[..end];
+        match cleaned[end..].starts_with(':') && is_valid_scheme(scheme) {
+            true => Some(scheme.to_ascii_lowercase()),
+            false => None,
+        }
+    }
+}
+
+impl std::convert::TryFrom<Url> for SourceValue {
+    type Error = String;
+
+    fn try_from(url: Url) -> Result<Self, Self::Error> {
+        SourceValue::from_url(url)
+    }
+}
+
+/// Checks urls as they are made into a `SourceValue`, see `SourceValue::try_new`. Inline
+/// `data:` images, for example, are made with
+/// `SourceCheck::new().allow_dangerous_scheme().try_new(..)`.
+pub struct SourceCheck {
+    allow_dangerous_scheme: bool,
+}
+
+impl SourceCheck {
+    pub fn new() -> Self {
+        SourceCheck {
+            allow_dangerous_scheme: false,
+        }
     }
+
+    pub fn allow_dangerous_scheme(mut self) -> Self {
+        self.allow_dangerous_scheme = true;
+        self
+    }
+
+    pub fn try_new(&self, s: String) -> Result<SourceValue, String> {
+        self.check(SourceValue::new(s))
+    }
+
+    pub fn from_url(&self, url: Url) -> Result<SourceValue, String> {
+        url.validate()?;
+        self.check(SourceValue {
+            inner: SourceInner::Url(url),
+        })
+    }
+
+    fn check(&self, value: SourceValue) -> Result<SourceValue, String> {
+        match value.scheme() {
+            Some(ref scheme)
+                if !self.allow_dangerous_scheme && DANGEROUS_SCHEMES.contains(&scheme.as_str()) =>
+            {
+                Err(format!("the {}: scheme is not allowed", scheme))
+            }
+            _ => Ok(value),
+        }
+    }
+}
+
+impl Default for SourceCheck {
+    fn default() -> Self {
+        SourceCheck::new()
+    }
+}
+
+fn is_valid_scheme(scheme: &str) -> bool {
+    let mut chars = scheme.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() => {
+            chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }
+        _ => false,
+    }
+}
+
+// A domain name or address, ipv6 addresses are wrapped in brackets
+fn is_valid_host(host: &str) -> bool {
+    match host.strip_prefix('[').and_then(|h| h.strip_suffix(']')) {
+        Some(ipv6) => {
+            !ipv6.is_empty()
+                && ipv6
+                    .chars()
+                    .all(|c| c.is_ascii_hexdigit() || matches!(c, ':' | '.'))
+        }
+        None => {
+            !host.is_empty()
+                && host
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.'))
+        }
+    }
+}
+
+/// A url built from parts, each part is percent-encoded when rendered.
+///
+/// Hosts are not converted to punycode: an internationalized domain name must be given in its
+/// ascii (`xn--`) form, or it is rejected by `validate`.
+pub struct Url {
+    scheme: Option<String>,
+    host: Option<String>,
+    port: Option<u16>,
+    rooted: bool,
+    segments: Vec<String>,
+    trailing_slash: bool,
+    query: Vec<(String, String)>,
+    fragment: Option<String>,
+}
+
+impl Url {
+    /// An absolute url, like `https://example.com`.
+    pub fn absolute<S: Into<String>, H: Into<String>>(scheme: S, host: H) -> Self {
+        Url {
+            scheme: Some(scheme.into()),
+            host: Some(host.into()),
+            ..Url::root()
+        }
+    }
+
+    /// A path from the root of the current host, like `/a/b`.
+    pub fn root() -> Self {
+        Url {
+            scheme: None,
+            host: None,
+            port: None,
+            rooted: true,
+            segments: Vec::new(),
+            trailing_slash: false,
+            query: Vec::new(),
+            fragment: None,
+        }
+    }
+
+    /// A path relative to the current document, like `a/b`.
+    pub fn relative() -> Self {
+        Url {
+            rooted: false,
+            ..Url::root()
+        }
+    }
+
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    pub fn with_segment<S: Into<String>>(mut self, segment: S) -> Self {
+        self.segments.push(segment.into());
+        self
+    }
+
+    pub fn with_segments<S: Into<String>, I: IntoIterator<Item = S>>(
+        mut self,
+        segments: I,
+    ) -> Self {
+        self.segments.extend(segments.into_iter().map(|s| s.into()));
+        self
+    }
+
+    pub fn with_trailing_slash(mut self) -> Self {
+        self.trailing_slash = true;
+        self
+    }
+
+    pub fn with_query<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.query.push((key.into(), value.into()));
+        self
+    }
+
+    pub fn with_fragment<S: Into<String>>(mut self, fragment: S) -> Self {
+        self.fragment = Some(fragment.into());
+        self
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(ref scheme) = self.scheme {
+            if !is_valid_scheme(scheme) {
+                return Err(format!("{:?} is not a valid url scheme", scheme));
+            }
+        }
+        if let Some(ref host) = self.host {
+            if !host.is_ascii() {
+                return Err(format!(
+                    "{:?} must be given in its ascii (punycode) form to be a url host",
+                    host
+                ));
+            }
+            if !is_valid_host(host) {
+                return Err(format!("{:?} is not a valid url host", host));
+            }
+        }
+        // An empty segment would leave a `//` in the path, which is read as the start of a host
+        // when it leads the url
+        if self.segments.iter().any(|s| s.is_empty()) {
+            return Err("url path segments can not be empty".into());
+        }
+        Ok(())
+    }
+
+    pub fn url_string(&self) -> String {
+        let mut ret = String::new();
+        if let Some(ref scheme) = self.scheme {
+            ret.push_str(&scheme.to_ascii_lowercase());
+            ret.push(':');
+        }
+        if let Some(ref host) = self.host {
+            ret.push_str("//");
+            ret.push_str(&host.to_ascii_lowercase());
+            if let Some(port) = self.port {
+                ret.push_str(&format!(":{}", port));
+            }
+        }
+        // A colon in the first segment of a relative path would be read as a scheme
+        let path = self
+            .segments
+            .iter()
+            .enumerate()
+            .map(|(i, s)| match i == 0 && !self.rooted {
+                true => percent_encode(s, RELATIVE_FIRST_SEGMENT_SAFE),
+                false => percent_encode(s, PATH_SAFE),
+            })
+            .collect::<Vec<String>>()
+            .join("/");
+        if self.rooted && (self.host.is_none() || !path.is_empty() || self.trailing_slash) {
+            ret.push('/');
+        }
+        ret.push_str(&path);
+        if self.trailing_slash && !path.is_empty() {
+            ret.push('/');
+        }
+        if !self.query.is_empty() {
+            ret.push('?');
+            ret.push_str(
+                &self
+                    .query
+                    .iter()
+                    .map(|(k, v)| {
+                        format!(
+                            "{}={}",
+                            percent_encode(k, QUERY_SAFE),
+                            percent_encode(v, QUERY_SAFE)
+                        )
+                    })
+                    .collect::<Vec<String>>()
+                    .join("&"),
+            );
+        }
+        if let Some(ref fragment) = self.fragment {
+            ret.push('#');
+            ret.push_str(&percent_encode(fragment, FRAGMENT_SAFE));
+        }
+        ret
+    }
+}
+
+// Characters left as is beyond the unreserved `A-Z a-z 0-9 - . _ ~`
+const PATH_SAFE: &str = "!$&'()*+,;=:@";
+const RELATIVE_FIRST_SEGMENT_SAFE: &str = "!$&'()*+,;=@";
+const QUERY_SAFE: &str = "!$'()*,;:@/?";
+const FRAGMENT_SAFE: &str = "!$&'()*+,;=:@/?";
+
+fn percent_encode(s: &str, safe: &str) -> String {
+    let mut ret = String::new();
+    for b in s.bytes() {
+        let c = b as char;
+        if c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~') || safe.contains(c) {
+            ret.push(c);
+        } else {
+            ret.push_str(&format!("%{:02X}", b));
+        }
+    }
+    ret
 }
 
 pub enum InputTypeValue {
@@ -92,17 +590,48 @@ impl FormmethodValue {
 
 pub enum Length {
     Pixel,
-    Centimeter,
+    Em,
+    Rem,
+    Ex,
+    Ch,
+    ViewportWidth,
     ViewportHeight,
+    ViewportMin,
+    ViewportMax,
+    SmallViewportHeight,
+    LargeViewportHeight,
+    DynamicViewportHeight,
+    Centimeter,
+    Millimeter,
+    QuarterMillimeter,
+    Inch,
+    Point,
+    Pica,
 }
 
 impl Length {
     pub fn unit_str(&self) -> String {
         match self {
-            Length::Pixel => "px".into(),
-            Length::Centimeter => "cm".into(),
-            Length::ViewportHeight => "vh".into(),
+            Length::Pixel => "px",
+            Length::Em => "em",
+            Length::Rem => "rem",
+            Length::Ex => "ex",
+            Length::Ch => "ch",
+            Length::ViewportWidth => "vw",
+            Length::ViewportHeight => "vh",
+            Length::ViewportMin => "vmin",
+            Length::ViewportMax => "vmax",
+            Length::SmallViewportHeight => "svh",
+            Length::LargeViewportHeight => "lvh",
+            Length::DynamicViewportHeight => "dvh",
+            Length::Centimeter => "cm",
+            Length::Millimeter => "mm",
+            Length::QuarterMillimeter => "Q",
+            Length::Inch => "in",
+            Length::Point => "pt",
+            Length::Pica => "pc",
         }
+        .into()
     }
 }
 
@@ -160,47 +689,2600 @@ impl VerticalAlignValue {
     }
 }
 
+/// A number which css can write: NaN and the infinities are rejected by `Finite::new`, while
+/// integers convert with `From`. The unit helpers take one, so `px(10)` needs no checking and
+/// `rem(Finite::new(1.5)?)` does.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct Finite(f64);
+
+impl Finite {
+    pub fn new<V: Into<f64>>(v: V) -> Result<Self, String> {
+        let v = v.into();
+        match v.is_finite() {
+            true => Ok(Finite(v)),
+            false => Err(format!("{} is not a finite number", v)),
+        }
+    }
+
+    pub fn get(self) -> f64 {
+        self.0
+    }
+
+    pub fn value_string(&self) -> String {
+        format_number(self.0)
+    }
+}
+
+impl std::convert::TryFrom<f64> for Finite {
+    type Error = String;
+
+    fn try_from(v: f64) -> Result<Self, Self::Error> {
+        Finite::new(v)
+    }
+}
+
+impl From<i8> for Finite {
+    fn from(v: i8) -> Self {
+        Finite(v.into())
+    }
+}
+
+impl From<i16> for Finite {
+    fn from(v: i16) -> Self {
+        Finite(v.into())
+    }
+}
+
+impl From<i32> for Finite {
+    fn from(v: i32) -> Self {
+        Finite(v.into())
+    }
+}
+
+impl From<u8> for Finite {
+    fn from(v: u8) -> Self {
+        Finite(v.into())
+    }
+}
+
+impl From<u16> for Finite {
+    fn from(v: u16) -> Self {
+        Finite(v.into())
+    }
+}
+
+impl From<u32> for Finite {
+    fn from(v: u32) -> Self {
+        Finite(v.into())
+    }
+}
+
 pub enum Number {
-    Length(u32, Length),
-    Percentage(u32),
+    Length(Finite, Length),
+    Percentage(Finite),
+    /// A length valued math expression, built with `Number::try_from`.
+    Math(Box<MathExpr>),
 }
 
 impl Number {
     pub fn style_value_helper(&self) -> String {
         match self {
-            Number::Length(v, l) => format!("{}{}", v, l.unit_str()),
-            Number::Percentage(v) => format!("{}{}", v, Percentage {}.unit_str()),
+            Number::Length(v, l) => format!("{}{}", v.value_string(), l.unit_str()),
+            Number::Percentage(v) => format!("{}{}", v.value_string(), Percentage {}.unit_str()),
+            Number::Math(m) => m.style_value_helper(),
         }
     }
 }
 
-pub enum NumberOrAuto {
-    Number(Number),
-    Auto,
+impl std::convert::TryFrom<MathExpr> for Number {
+    type Error = String;
+
+    fn try_from(m: MathExpr) -> Result<Self, Self::Error> {
+        match m.dimension {
+            Dimension::Length => Ok(Number::Math(Box::new(m))),
+            Dimension::Scalar => Err(format!(
+                "{} is a plain number, not a length",
+                m.style_value_helper()
+            )),
+        }
+    }
 }
 
-impl NumberOrAuto {
-    pub fn style_value_helper(&self) -> String {
+#[derive(Clone, Copy, PartialEq)]
+enum Dimension {
+    Scalar,
+    /// Lengths and percentages, which may be mixed
+    Length,
+}
+
+enum MathNode {
+    Value(Number),
+    Scalar(Finite),
+    Sum(Box<MathNode>, char, Box<MathNode>),
+    Product(Box<MathNode>, char, Box<MathNode>),
+    Function(&'static str, Vec<MathNode>),
+}
+
+impl MathNode {
+    fn write(&self, ret: &mut String) {
         match self {
-            NumberOrAuto::Number(n) => n.style_value_helper(),
-            NumberOrAuto::Auto => Auto {}.unit_str(),
+            MathNode::Value(n) => ret.push_str(&n.style_value_helper()),
+            MathNode::Scalar(v) => ret.push_str(&v.value_string()),
+            MathNode::Sum(l, op, r) => {
+                l.write(ret);
+                ret.push_str(&format!(" {} ", op));
+                r.write_grouped(ret, matches!(**r, MathNode::Sum(..)));
+            }
+            MathNode::Product(l, op, r) => {
+                l.write_grouped(ret, matches!(**l, MathNode::Sum(..)));
+                ret.push_str(&format!(" {} ", op));
+                r.write_grouped(
+                    ret,
+                    matches!(**r, MathNode::Sum(..) | MathNode::Product(..)),
+                );
+            }
+            MathNode::Function(name, args) => {
+                ret.push_str(name);
+                ret.push('(');
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        ret.push_str(", ");
+                    }
+                    arg.write(ret);
+                }
+                ret.push(')');
+            }
+        }
+    }
+
+    fn write_grouped(&self, ret: &mut String, grouped: bool) {
+        if grouped {
+            ret.push('(');
+        }
+        self.write(ret);
+        if grouped {
+            ret.push(')');
         }
     }
 }
 
-pub enum VisibilityValue {
-    Visible,
-    Hidden,
-    Collapse,
+/// A `calc()`, `min()`, `max()` or `clamp()` expression. Each step checks the dimensions of
+/// its operands, so `px * px` or `10px + 2` fail to build.
+pub struct MathExpr {
+    node: MathNode,
+    dimension: Dimension,
 }
 
-impl VisibilityValue {
+impl MathExpr {
+    pub fn length(n: Number) -> Self {
+        match n {
+            Number::Math(m) => *m,
+            n => MathExpr {
+                node: MathNode::Value(n),
+                dimension: Dimension::Length,
+            },
+        }
+    }
+
+    pub fn scalar<V: Into<Finite>>(v: V) -> Self {
+        MathExpr {
+            node: MathNode::Scalar(v.into()),
+            dimension: Dimension::Scalar,
+        }
+    }
+
+    pub fn plus(self, other: MathExpr) -> Result<Self, String> {
+        self.sum('+', other)
+    }
+
+    pub fn minus(self, other: MathExpr) -> Result<Self, String> {
+        self.sum('-', other)
+    }
+
+    pub fn times(self, other: MathExpr) -> Result<Self, String> {
+        if self.dimension == Dimension::Length && other.dimension == Dimension::Length {
+            return Err("can not multiply two lengths".into());
+        }
+        let dimension = match self.dimension == Dimension::Length {
+            true => Dimension::Length,
+            false => other.dimension,
+        };
+        Ok(MathExpr {
+            node: MathNode::Product(Box::new(self.node), '*', Box::new(other.node)),
+            dimension,
+        })
+    }
+
+    pub fn divided_by(self, other: MathExpr) -> Result<Self, String> {
+        if other.dimension == Dimension::Length {
+            return Err("can not divide by a length".into());
+        }
+        if let MathNode::Scalar(v) = other.node {
+            if v.get() == 0.0 {
+                return Err("can not divide by zero".into());
+            }
+        }
+        Ok(MathExpr {
+            node: MathNode::Product(Box::new(self.node), '/', Box::new(other.node)),
+            dimension: self.dimension,
+        })
+    }
+
+    pub fn min(args: Vec<MathExpr>) -> Result<Self, String> {
+        MathExpr::function("min", args)
+    }
+
+    pub fn max(args: Vec<MathExpr>) -> Result<Self, String> {
+        MathExpr::function("max", args)
+    }
+
+    pub fn clamp(min: MathExpr, preferred: MathExpr, max: MathExpr) -> Result<Self, String> {
+        MathExpr::function("clamp", vec![min, preferred, max])
+    }
+
     pub fn style_value_helper(&self) -> String {
-        match self {
-            VisibilityValue::Visible => "visible",
-            VisibilityValue::Hidden => "hidden",
-            VisibilityValue::Collapse => "collapse",
+        let mut ret = String::new();
+        match self.node {
+            MathNode::Function(..) => self.node.write(&mut ret),
+            _ => {
+                ret.push_str("calc(");
+                self.node.write(&mut ret);
+                ret.push(')');
+            }
         }
-        .into()
+        ret
+    }
+
+    fn sum(self, op: char, other: MathExpr) -> Result<Self, String> {
+        if self.dimension != other.dimension {
+            return Err(format!(
+                "can not {} a length and a plain number",
+                match op {
+                    '+' => "add",
+                    _ => "subtract",
+                }
+            ));
+        }
+        Ok(MathExpr {
+            node: MathNode::Sum(Box::new(self.node), op, Box::new(other.node)),
+            dimension: self.dimension,
+        })
+    }
+
+    fn function(name: &'static str, args: Vec<MathExpr>) -> Result<Self, String> {
+        let dimension = match args.first() {
+            Some(a) => a.dimension,
+            None => return Err(format!("{}() needs at least one argument", name)),
+        };
+        if args.iter().any(|a| a.dimension != dimension) {
+            return Err(format!("{}() can not mix lengths and plain numbers", name));
+        }
+        Ok(MathExpr {
+            node: MathNode::Function(name, args.into_iter().map(|a| a.node).collect()),
+            dimension,
+        })
+    }
+}
+
+impl From<Number> for MathExpr {
+    fn from(n: Number) -> Self {
+        MathExpr::length(n)
+    }
+}
+
+pub fn px<V: Into<Finite>>(v: V) -> Number {
+    Number::Length(v.into(), Length::Pixel)
+}
+
+pub fn em<V: Into<Finite>>(v: V) -> Number {
+    Number::Length(v.into(), Length::Em)
+}
+
+pub fn rem<V: Into<Finite>>(v: V) -> Number {
+    Number::Length(v.into(), Length::Rem)
+}
+
+pub fn ex<V: Into<Finite>>(v: V) -> Number {
+    Number::Length(v.into(), Length::Ex)
+}
+
+pub fn ch<V: Into<Finite>>(v: V) -> Number {
+    Number::Length(v.into(), Length::Ch)
+}
+
+pub fn vw<V: Into<Finite>>(v: V) -> Number {
+    Number::Length(v.into(), Length::ViewportWidth)
+}
+
+pub fn vh<V: Into<Finite>>(v: V) -> Number {
+    Number::Length(v.into(), Length::ViewportHeight)
+}
+
+pub fn vmin<V: Into<Finite>>(v: V) -> Number {
+    Number::Length(v.into(), Length::ViewportMin)
+}
+
+pub fn vmax<V: Into<Finite>>(v: V) -> Number {
+    Number::Length(v.into(), Length::ViewportMax)
+}
+
+pub fn svh<V: Into<Finite>>(v: V) -> Number {
+    Number::Length(v.into(), Length::SmallViewportHeight)
+}
+
+pub fn lvh<V: Into<Finite>>(v: V) -> Number {
+    Number::Length(v.into(), Length::LargeViewportHeight)
+}
+
+pub fn dvh<V: Into<Finite>>(v: V) -> Number {
+    Number::Length(v.into(), Length::DynamicViewportHeight)
+}
+
+pub fn cm<V: Into<Finite>>(v: V) -> Number {
+    Number::Length(v.into(), Length::Centimeter)
+}
+
+pub fn mm<V: Into<Finite>>(v: V) -> Number {
+    Number::Length(v.into(), Length::Millimeter)
+}
+
+pub fn q<V: Into<Finite>>(v: V) -> Number {
+    Number::Length(v.into(), Length::QuarterMillimeter)
+}
+
+pub fn inch<V: Into<Finite>>(v: V) -> Number {
+    Number::Length(v.into(), Length::Inch)
+}
+
+pub fn pt<V: Into<Finite>>(v: V) -> Number {
+    Number::Length(v.into(), Length::Point)
+}
+
+pub fn pc<V: Into<Finite>>(v: V) -> Number {
+    Number::Length(v.into(), Length::Pica)
+}
+
+pub fn percent<V: Into<Finite>>(v: V) -> Number {
+    Number::Percentage(v.into())
+}
+
+/// A fraction of the free space in a grid container, like `1fr`.
+pub struct Flex {
+    pub value: Finite,
+}
+
+impl Flex {
+    pub fn style_value_helper(&self) -> String {
+        format!("{}fr", self.value.value_string())
+    }
+}
+
+pub fn fr<V: Into<Finite>>(v: V) -> Flex {
+    Flex { value: v.into() }
+}
+
+/// How much a flex item grows or shrinks relative to its siblings, which can not be negative.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub struct FlexFactor(Finite);
+
+impl FlexFactor {
+    pub fn new<V: Into<Finite>>(v: V) -> Result<Self, String> {
+        let v = v.into();
+        match v.get() < 0.0 {
+            true => Err(format!(
+                "flex factor {} can not be negative",
+                v.value_string()
+            )),
+            false => Ok(FlexFactor(v)),
+        }
+    }
+
+    pub fn get(self) -> Finite {
+        self.0
+    }
+
+    pub fn value_string(&self) -> String {
+        self.0.value_string()
+    }
+}
+
+impl From<u32> for FlexFactor {
+    fn from(v: u32) -> Self {
+        FlexFactor(v.into())
+    }
+}
+
+pub enum NumberOrAuto {
+    Number(Number),
+    Auto,
+}
+
+impl From<Number> for NumberOrAuto {
+    fn from(n: Number) -> Self {
+        NumberOrAuto::Number(n)
+    }
+}
+
+impl From<Auto> for NumberOrAuto {
+    fn from(_: Auto) -> Self {
+        NumberOrAuto::Auto
+    }
+}
+
+impl NumberOrAuto {
+    pub fn style_value_helper(&self) -> String {
+        match self {
+            NumberOrAuto::Number(n) => n.style_value_helper(),
+            NumberOrAuto::Auto => Auto {}.unit_str(),
+        }
+    }
+}
+
+pub enum VisibilityValue {
+    Visible,
+    Hidden,
+    Collapse,
+}
+
+impl VisibilityValue {
+    pub fn style_value_helper(&self) -> String {
+        match self {
+            VisibilityValue::Visible => "visible",
+            VisibilityValue::Hidden => "hidden",
+            VisibilityValue::Collapse => "collapse",
+        }
+        .into()
+    }
+}
+
+pub enum DirValue {
+    Ltr,
+    Rtl,
+    Auto,
+}
+
+impl DirValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            DirValue::Ltr => "ltr",
+            DirValue::Rtl => "rtl",
+            DirValue::Auto => "auto",
+        }
+        .into()
+    }
+}
+
+pub enum ContenteditableValue {
+    True,
+    False,
+    PlaintextOnly,
+}
+
+impl ContenteditableValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            ContenteditableValue::True => "true",
+            ContenteditableValue::False => "false",
+            ContenteditableValue::PlaintextOnly => "plaintext-only",
+        }
+        .into()
+    }
+}
+
+/// The non-abstract WAI-ARIA roles.
+pub enum RoleValue {
+    Alert,
+    Alertdialog,
+    Application,
+    Article,
+    Banner,
+    Blockquote,
+    Button,
+    Caption,
+    Cell,
+    Checkbox,
+    Code,
+    Columnheader,
+    Combobox,
+    Complementary,
+    Contentinfo,
+    Definition,
+    Deletion,
+    Dialog,
+    Document,
+    Emphasis,
+    Feed,
+    Figure,
+    Form,
+    Generic,
+    Grid,
+    Gridcell,
+    Group,
+    Heading,
+    Img,
+    Insertion,
+    Link,
+    List,
+    Listbox,
+    Listitem,
+    Log,
+    Main,
+    Marquee,
+    Math,
+    Menu,
+    Menubar,
+    Menuitem,
+    Menuitemcheckbox,
+    Menuitemradio,
+    Meter,
+    Navigation,
+    None,
+    Note,
+    Option,
+    Paragraph,
+    Presentation,
+    Progressbar,
+    Radio,
+    Radiogroup,
+    Region,
+    Row,
+    Rowgroup,
+    Rowheader,
+    Scrollbar,
+    Search,
+    Searchbox,
+    Separator,
+    Slider,
+    Spinbutton,
+    Status,
+    Strong,
+    Subscript,
+    Superscript,
+    Switch,
+    Tab,
+    Table,
+    Tablist,
+    Tabpanel,
+    Term,
+    Textbox,
+    Time,
+    Timer,
+    Toolbar,
+    Tooltip,
+    Tree,
+    Treegrid,
+    Treeitem,
+}
+
+impl RoleValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            RoleValue::Alert => "alert",
+            RoleValue::Alertdialog => "alertdialog",
+            RoleValue::Application => "application",
+            RoleValue::Article => "article",
+            RoleValue::Banner => "banner",
+            RoleValue::Blockquote => "blockquote",
+            RoleValue::Button => "button",
+            RoleValue::Caption => "caption",
+            RoleValue::Cell => "cell",
+            RoleValue::Checkbox => "checkbox",
+            RoleValue::Code => "code",
+            RoleValue::Columnheader => "columnheader",
+            RoleValue::Combobox => "combobox",
+            RoleValue::Complementary => "complementary",
+            RoleValue::Contentinfo => "contentinfo",
+            RoleValue::Definition => "definition",
+            RoleValue::Deletion => "deletion",
+            RoleValue::Dialog => "dialog",
+            RoleValue::Document => "document",
+            RoleValue::Emphasis => "emphasis",
+            RoleValue::Feed => "feed",
+            RoleValue::Figure => "figure",
+            RoleValue::Form => "form",
+            RoleValue::Generic => "generic",
+            RoleValue::Grid => "grid",
+            RoleValue::Gridcell => "gridcell",
+            RoleValue::Group => "group",
+            RoleValue::Heading => "heading",
+            RoleValue::Img => "img",
+            RoleValue::Insertion => "insertion",
+            RoleValue::Link => "link",
+            RoleValue::List => "list",
+            RoleValue::Listbox => "listbox",
+            RoleValue::Listitem => "listitem",
+            RoleValue::Log => "log",
+            RoleValue::Main => "main",
+            RoleValue::Marquee => "marquee",
+            RoleValue::Math => "math",
+            RoleValue::Menu => "menu",
+            RoleValue::Menubar => "menubar",
+            RoleValue::Menuitem => "menuitem",
+            RoleValue::Menuitemcheckbox => "menuitemcheckbox",
+            RoleValue::Menuitemradio => "menuitemradio",
+            RoleValue::Meter => "meter",
+            RoleValue::Navigation => "navigation",
+            RoleValue::None => "none",
+            RoleValue::Note => "note",
+            RoleValue::Option => "option",
+            RoleValue::Paragraph => "paragraph",
+            RoleValue::Presentation => "presentation",
+            RoleValue::Progressbar => "progressbar",
+            RoleValue::Radio => "radio",
+            RoleValue::Radiogroup => "radiogroup",
+            RoleValue::Region => "region",
+            RoleValue::Row => "row",
+            RoleValue::Rowgroup => "rowgroup",
+            RoleValue::Rowheader => "rowheader",
+            RoleValue::Scrollbar => "scrollbar",
+            RoleValue::Search => "search",
+            RoleValue::Searchbox => "searchbox",
+            RoleValue::Separator => "separator",
+            RoleValue::Slider => "slider",
+            RoleValue::Spinbutton => "spinbutton",
+            RoleValue::Status => "status",
+            RoleValue::Strong => "strong",
+            RoleValue::Subscript => "subscript",
+            RoleValue::Superscript => "superscript",
+            RoleValue::Switch => "switch",
+            RoleValue::Tab => "tab",
+            RoleValue::Table => "table",
+            RoleValue::Tablist => "tablist",
+            RoleValue::Tabpanel => "tabpanel",
+            RoleValue::Term => "term",
+            RoleValue::Textbox => "textbox",
+            RoleValue::Time => "time",
+            RoleValue::Timer => "timer",
+            RoleValue::Toolbar => "toolbar",
+            RoleValue::Tooltip => "tooltip",
+            RoleValue::Tree => "tree",
+            RoleValue::Treegrid => "treegrid",
+            RoleValue::Treeitem => "treeitem",
+        }
+        .into()
+    }
+}
+
+/// The id of another element, referenced from an `aria-*` attribute. It can not be empty or
+/// hold any whitespace.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IdRef(String);
+
+impl IdRef {
+    pub fn new<S: Into<String>>(id: S) -> Result<Self, String> {
+        let id = id.into();
+        if id.is_empty() || id.chars().any(char::is_whitespace) {
+            return Err(format!(
+                "id reference {:?} must be non-empty and contain no whitespace",
+                id
+            ));
+        }
+        Ok(IdRef(id))
+    }
+
+    pub fn value_string(&self) -> String {
+        self.0.clone()
+    }
+}
+
+/// The number of items in a set, or of rows or columns in a table, `Unknown` when not all of
+/// them are in the page.
+pub enum AriaSizeValue {
+    Known(std::num::NonZeroU32),
+    Unknown,
+}
+
+impl AriaSizeValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            AriaSizeValue::Known(v) => v.to_string(),
+            AriaSizeValue::Unknown => "-1".into(),
+        }
+    }
+}
+
+pub enum Tristate {
+    True,
+    False,
+    Mixed,
+}
+
+impl Tristate {
+    pub fn value_string(&self) -> String {
+        match self {
+            Tristate::True => "true",
+            Tristate::False => "false",
+            Tristate::Mixed => "mixed",
+        }
+        .into()
+    }
+}
+
+pub enum AriaLiveValue {
+    Off,
+    Polite,
+    Assertive,
+}
+
+impl AriaLiveValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            AriaLiveValue::Off => "off",
+            AriaLiveValue::Polite => "polite",
+            AriaLiveValue::Assertive => "assertive",
+        }
+        .into()
+    }
+}
+
+pub enum AriaCurrentValue {
+    Page,
+    Step,
+    Location,
+    Date,
+    Time,
+    True,
+    False,
+}
+
+impl AriaCurrentValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            AriaCurrentValue::Page => "page",
+            AriaCurrentValue::Step => "step",
+            AriaCurrentValue::Location => "location",
+            AriaCurrentValue::Date => "date",
+            AriaCurrentValue::Time => "time",
+            AriaCurrentValue::True => "true",
+            AriaCurrentValue::False => "false",
+        }
+        .into()
+    }
+}
+
+pub enum AriaHaspopupValue {
+    False,
+    True,
+    Menu,
+    Listbox,
+    Tree,
+    Grid,
+    Dialog,
+}
+
+impl AriaHaspopupValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            AriaHaspopupValue::False => "false",
+            AriaHaspopupValue::True => "true",
+            AriaHaspopupValue::Menu => "menu",
+            AriaHaspopupValue::Listbox => "listbox",
+            AriaHaspopupValue::Tree => "tree",
+            AriaHaspopupValue::Grid => "grid",
+            AriaHaspopupValue::Dialog => "dialog",
+        }
+        .into()
+    }
+}
+
+pub enum AriaInvalidValue {
+    False,
+    True,
+    Grammar,
+    Spelling,
+}
+
+impl AriaInvalidValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            AriaInvalidValue::False => "false",
+            AriaInvalidValue::True => "true",
+            AriaInvalidValue::Grammar => "grammar",
+            AriaInvalidValue::Spelling => "spelling",
+        }
+        .into()
+    }
+}
+
+pub enum AriaOrientationValue {
+    Horizontal,
+    Vertical,
+}
+
+impl AriaOrientationValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            AriaOrientationValue::Horizontal => "horizontal",
+            AriaOrientationValue::Vertical => "vertical",
+        }
+        .into()
+    }
+}
+
+pub enum AriaSortValue {
+    None,
+    Ascending,
+    Descending,
+    Other,
+}
+
+impl AriaSortValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            AriaSortValue::None => "none",
+            AriaSortValue::Ascending => "ascending",
+            AriaSortValue::Descending => "descending",
+            AriaSortValue::Other => "other",
+        }
+        .into()
+    }
+}
+
+pub enum AriaAutocompleteValue {
+    None,
+    Inline,
+    List,
+    Both,
+}
+
+impl AriaAutocompleteValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            AriaAutocompleteValue::None => "none",
+            AriaAutocompleteValue::Inline => "inline",
+            AriaAutocompleteValue::List => "list",
+            AriaAutocompleteValue::Both => "both",
+        }
+        .into()
+    }
+}
+
+/// A CSS color. Operations like `lighten` and `contrast_ratio` need concrete channels, so they
+/// return `None` for `CurrentColor`.
+pub enum ColorValue {
+    Named(NamedColor),
+    /// Rendered as `#rrggbb`, or `#rrggbbaa` when the alpha is below 255.
+    Hex(u8, u8, u8, u8),
+    /// Red, green and blue, with an alpha from 0 to 1.
+    Rgb(u8, u8, u8, Finite),
+    /// Hue in degrees, saturation and lightness in percent, with an alpha from 0 to 1.
+    Hsl(Finite, Finite, Finite, Finite),
+    CurrentColor,
+    Transparent,
+}
+
+impl ColorValue {
+    pub fn rgb(red: u8, green: u8, blue: u8) -> Self {
+        ColorValue::Rgb(red, green, blue, Finite(1.0))
+    }
+
+    pub fn hex(value: u32) -> Self {
+        ColorValue::Hex((value >> 16) as u8, (value >> 8) as u8, value as u8, 255)
+    }
+
+    pub fn hsl(hue: f64, saturation: f64, lightness: f64) -> Result<Self, String> {
+        Ok(ColorValue::Hsl(
+            Finite::new(hue)?,
+            Finite::new(saturation)?,
+            Finite::new(lightness)?,
+            Finite(1.0),
+        ))
+    }
+
+    /// Parses any of the forms this type renders, along with `rgba()`, `hsla()`, short hex
+    /// forms and the space separated `rgb(r g b / a)` syntax.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let err = || format!("invalid color {:?}", s);
+        let lower = s.trim().to_ascii_lowercase();
+        if lower == "currentcolor" {
+            return Ok(ColorValue::CurrentColor);
+        }
+        if lower == "transparent" {
+            return Ok(ColorValue::Transparent);
+        }
+        if let Some(named) = NamedColor::from_name(&lower) {
+            return Ok(ColorValue::Named(named));
+        }
+        if let Some(hex) = lower.strip_prefix('#') {
+            if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(err());
+            }
+            let digits: Vec<u8> = match hex.len() {
+                3 | 4 => hex
+                    .chars()
+                    .map(|c| c.to_digit(16).unwrap() as u8 * 17)
+                    .collect(),
+                6 | 8 => (0..hex.len())
+                    .step_by(2)
+                    .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+                    .collect(),
+                _ => return Err(err()),
+            };
+            return Ok(ColorValue::Hex(
+                digits[0],
+                digits[1],
+                digits[2],
+                *digits.get(3).unwrap_or(&255),
+            ));
+        }
+
+        let open = lower.find('(').ok_or_else(err)?;
+        let inner = lower[open + 1..].strip_suffix(')').ok_or_else(err)?;
+        let args: Vec<&str> = inner
+            .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+            .filter(|a| !a.is_empty())
+            .collect();
+        if args.len() != 3 && args.len() != 4 {
+            return Err(err());
+        }
+        let alpha = match args.get(3) {
+            Some(a) => parse_color_number(a, 1.0).ok_or_else(err)?,
+            None => 1.0,
+        };
+        match &lower[..open] {
+            "rgb" | "rgba" => {
+                let mut channels = [0; 3];
+                for (channel, arg) in channels.iter_mut().zip(args.iter()) {
+                    *channel = parse_color_number(arg, 255.0)
+                        .ok_or_else(err)?
+                        .round()
+                        .clamp(0.0, 255.0) as u8;
+                }
+                Ok(ColorValue::Rgb(
+                    channels[0],
+                    channels[1],
+                    channels[2],
+                    Finite(alpha.clamp(0.0, 1.0)),
+                ))
+            }
+            "hsl" | "hsla" => {
+                let hue = args[0].strip_suffix("deg").unwrap_or(args[0]);
+                let percent = |a: &str| a.strip_suffix('%').and_then(parse_finite);
+                Ok(ColorValue::Hsl(
+                    Finite(parse_finite(hue).ok_or_else(err)?),
+                    Finite(percent(args[1]).ok_or_else(err)?.clamp(0.0, 100.0)),
+                    Finite(percent(args[2]).ok_or_else(err)?.clamp(0.0, 100.0)),
+                    Finite(alpha.clamp(0.0, 1.0)),
+                ))
+            }
+            _ => Err(err()),
+        }
+    }
+
+    /// The red, green and blue channels from 0 to 255 and the alpha from 0 to 1.
+    pub fn to_rgba(&self) -> Option<(f64, f64, f64, f64)> {
+        match *self {
+            ColorValue::Named(ref n) => Some((n.red as f64, n.green as f64, n.blue as f64, 1.0)),
+            ColorValue::Hex(r, g, b, a) => Some((r as f64, g as f64, b as f64, a as f64 / 255.0)),
+            ColorValue::Rgb(r, g, b, a) => Some((r as f64, g as f64, b as f64, a.get())),
+            ColorValue::Hsl(h, s, l, a) => {
+                let (r, g, b) = hsl_to_rgb(h.get(), s.get() / 100.0, l.get() / 100.0);
+                Some((r * 255.0, g * 255.0, b * 255.0, a.get()))
+            }
+            ColorValue::CurrentColor => None,
+            ColorValue::Transparent => Some((0.0, 0.0, 0.0, 0.0)),
+        }
+    }
+
+    /// Raises the lightness by `amount` percentage points, `None` when the amount is not finite.
+    pub fn lighten(&self, amount: f64) -> Option<Self> {
+        self.adjust_lightness(amount)
+    }
+
+    /// Lowers the lightness by `amount` percentage points, `None` when the amount is not finite.
+    pub fn darken(&self, amount: f64) -> Option<Self> {
+        self.adjust_lightness(-amount)
+    }
+
+    /// Mixes the two colors, `weight` from 0 to 1 being how much of `self` to use. `None` when
+    /// the weight is not a number.
+    pub fn mix(&self, other: &ColorValue, weight: f64) -> Option<Self> {
+        if weight.is_nan() {
+            return None;
+        }
+        let (r1, g1, b1, a1) = self.to_rgba()?;
+        let (r2, g2, b2, a2) = other.to_rgba()?;
+        let w = weight.clamp(0.0, 1.0);
+        let blend = |x: f64, y: f64| (x * w + y * (1.0 - w)).round() as u8;
+        Some(ColorValue::Rgb(
+            blend(r1, r2),
+            blend(g1, g2),
+            blend(b1, b2),
+            Finite(a1 * w + a2 * (1.0 - w)),
+        ))
+    }
+
+    /// The same color with the alpha replaced, `None` when the alpha is not a number.
+    pub fn with_alpha(&self, alpha: f64) -> Option<Self> {
+        if alpha.is_nan() {
+            return None;
+        }
+        let alpha = Finite(alpha.clamp(0.0, 1.0));
+        match *self {
+            ColorValue::Hsl(h, s, l, _) => Some(ColorValue::Hsl(h, s, l, alpha)),
+            ColorValue::Hex(r, g, b, _) => Some(ColorValue::Hex(
+                r,
+                g,
+                b,
+                (alpha.get() * 255.0).round() as u8,
+            )),
+            _ => {
+                let (r, g, b, _) = self.to_rgba()?;
+                let channel = |c: f64| c.round().clamp(0.0, 255.0) as u8;
+                Some(ColorValue::Rgb(channel(r), channel(g), channel(b), alpha))
+            }
+        }
+    }
+
+    /// The WCAG relative luminance, ignoring alpha.
+    pub fn relative_luminance(&self) -> Option<f64> {
+        let (r, g, b, _) = self.to_rgba()?;
+        let linear = |c: f64| {
+            let c = c / 255.0;
+            match c <= 0.03928 {
+                true => c / 12.92,
+                false => ((c + 0.055) / 1.055).powf(2.4),
+            }
+        };
+        Some(0.2126 * linear(r) + 0.7152 * linear(g) + 0.0722 * linear(b))
+    }
+
+    /// The WCAG contrast ratio between the two colors, from 1 to 21.
+    pub fn contrast_ratio(&self, other: &ColorValue) -> Option<f64> {
+        let l1 = self.relative_luminance()?;
+        let l2 = other.relative_luminance()?;
+        Some((l1.max(l2) + 0.05) / (l1.min(l2) + 0.05))
+    }
+
+    pub fn value_string(&self) -> String {
+        match *self {
+            ColorValue::Named(ref n) => n.name.into(),
+            ColorValue::Hex(r, g, b, 255) => format!("#{:02x}{:02x}{:02x}", r, g, b),
+            ColorValue::Hex(r, g, b, a) => format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a),
+            ColorValue::Rgb(r, g, b, a) if a.get() >= 1.0 => format!("rgb({}, {}, {})", r, g, b),
+            ColorValue::Rgb(r, g, b, a) => {
+                format!("rgba({}, {}, {}, {})", r, g, b, a.value_string())
+            }
+            ColorValue::Hsl(h, s, l, a) if a.get() >= 1.0 => format!(
+                "hsl({}, {}%, {}%)",
+                h.value_string(),
+                s.value_string(),
+                l.value_string()
+            ),
+            ColorValue::Hsl(h, s, l, a) => format!(
+                "hsla({}, {}%, {}%, {})",
+                h.value_string(),
+                s.value_string(),
+                l.value_string(),
+                a.value_string()
+            ),
+            ColorValue::CurrentColor => "currentColor".into(),
+            ColorValue::Transparent => "transparent".into(),
+        }
+    }
+
+    fn adjust_lightness(&self, amount: f64) -> Option<Self> {
+        if !amount.is_finite() {
+            return None;
+        }
+        let (r, g, b, a) = self.to_rgba()?;
+        let (h, s, l) = rgb_to_hsl(r / 255.0, g / 255.0, b / 255.0);
+        Some(ColorValue::Hsl(
+            Finite(h),
+            Finite(s * 100.0),
+            Finite((l * 100.0 + amount).clamp(0.0, 100.0)),
+            Finite(a),
+        ))
+    }
+}
+
+impl std::str::FromStr for ColorValue {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ColorValue::parse(s)
+    }
+}
+
+/// One of the CSS named colors, built with `NamedColor::from_name`.
+pub struct NamedColor {
+    name: &'static str,
+    red: u8,
+    green: u8,
+    blue: u8,
+}
+
+impl NamedColor {
+    pub fn from_name(name: &str) -> Option<Self> {
+        NAMED_COLORS
+            .iter()
+            .find(|(n, _, _, _)| n.eq_ignore_ascii_case(name))
+            .map(|&(name, red, green, blue)| NamedColor {
+                name,
+                red,
+                green,
+                blue,
+            })
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+// A plain number, or a percentage of `percent_of`
+fn parse_color_number(s: &str, percent_of: f64) -> Option<f64> {
+    match s.strip_suffix('%') {
+        Some(p) => parse_finite(p).map(|v| v / 100.0 * percent_of),
+        None => parse_finite(s),
+    }
+}
+
+// Rust also parses `nan`, `inf` and `infinity`, which are not css numbers
+fn parse_finite(s: &str) -> Option<f64> {
+    s.parse::<f64>().ok().filter(|v| v.is_finite())
+}
+
+// Takes the hue in degrees, saturation and lightness from 0 to 1, returns channels from 0 to 1
+fn hsl_to_rgb(h: f64, s: f64, l: f64) -> (f64, f64, f64) {
+    let f = |n: f64| {
+        let k = (n + h.rem_euclid(360.0) / 30.0) % 12.0;
+        let a = s * l.min(1.0 - l);
+        l - a * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+    };
+    (f(0.0), f(8.0), f(4.0))
+}
+
+// Takes channels from 0 to 1, returns the hue in degrees, saturation and lightness from 0 to 1
+fn rgb_to_hsl(r: f64, g: f64, b: f64) -> (f64, f64, f64) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+    let d = max - min;
+    if d == 0.0 {
+        return (0.0, 0.0, l);
+    }
+    let s = d / (1.0 - (2.0 * l - 1.0).abs());
+    let h = if max == r {
+        ((g - b) / d).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / d + 2.0
+    } else {
+        (r - g) / d + 4.0
+    };
+    (h * 60.0, s, l)
+}
+
+// Rounds to at most four decimal places and drops trailing zeros
+pub(crate) fn format_number(v: f64) -> String {
+    let rounded = (v * 10000.0).round() / 10000.0;
+    match rounded == 0.0 {
+        true => "0".into(),
+        false => rounded.to_string(),
+    }
+}
+
+const NAMED_COLORS: &[(&str, u8, u8, u8)] = &[
+    ("aliceblue", 0xf0, 0xf8, 0xff),
+    ("antiquewhite", 0xfa, 0xeb, 0xd7),
+    ("aqua", 0x00, 0xff, 0xff),
+    ("aquamarine", 0x7f, 0xff, 0xd4),
+    ("azure", 0xf0, 0xff, 0xff),
+    ("beige", 0xf5, 0xf5, 0xdc),
+    ("bisque", 0xff, 0xe4, 0xc4),
+    ("black", 0x00, 0x00, 0x00),
+    ("blanchedalmond", 0xff, 0xeb, 0xcd),
+    ("blue", 0x00, 0x00, 0xff),
+    ("blueviolet", 0x8a, 0x2b, 0xe2),
+    ("brown", 0xa5, 0x2a, 0x2a),
+    ("burlywood", 0xde, 0xb8, 0x87),
+    ("cadetblue", 0x5f, 0x9e, 0xa0),
+    ("chartreuse", 0x7f, 0xff, 0x00),
+    ("chocolate", 0xd2, 0x69, 0x1e),
+    ("coral", 0xff, 0x7f, 0x50),
+    ("cornflowerblue", 0x64, 0x95, 0xed),
+    ("cornsilk", 0xff, 0xf8, 0xdc),
+    ("crimson", 0xdc, 0x14, 0x3c),
+    ("cyan", 0x00, 0xff, 0xff),
+    ("darkblue", 0x00, 0x00, 0x8b),
+    ("darkcyan", 0x00, 0x8b, 0x8b),
+    ("darkgoldenrod", 0xb8, 0x86, 0x0b),
+    ("darkgray", 0xa9, 0xa9, 0xa9),
+    ("darkgreen", 0x00, 0x64, 0x00),
+    ("darkgrey", 0xa9, 0xa9, 0xa9),
+    ("darkkhaki", 0xbd, 0xb7, 0x6b),
+    ("darkmagenta", 0x8b, 0x00, 0x8b),
+    ("darkolivegreen", 0x55, 0x6b, 0x2f),
+    ("darkorange", 0xff, 0x8c, 0x00),
+    ("darkorchid", 0x99, 0x32, 0xcc),
+    ("darkred", 0x8b, 0x00, 0x00),
+    ("darksalmon", 0xe9, 0x96, 0x7a),
+    ("darkseagreen", 0x8f, 0xbc, 0x8f),
+    ("darkslateblue", 0x48, 0x3d, 0x8b),
+    ("darkslategray", 0x2f, 0x4f, 0x4f),
+    ("darkslategrey", 0x2f, 0x4f, 0x4f),
+    ("darkturquoise", 0x00, 0xce, 0xd1),
+    ("darkviolet", 0x94, 0x00, 0xd3),
+    ("deeppink", 0xff, 0x14, 0x93),
+    ("deepskyblue", 0x00, 0xbf, 0xff),
+    ("dimgray", 0x69, 0x69, 0x69),
+    ("dimgrey", 0x69, 0x69, 0x69),
+    ("dodgerblue", 0x1e, 0x90, 0xff),
+    ("firebrick", 0xb2, 0x22, 0x22),
+    ("floralwhite", 0xff, 0xfa, 0xf0),
+    ("forestgreen", 0x22, 0x8b, 0x22),
+    ("fuchsia", 0xff, 0x00, 0xff),
+    ("gainsboro", 0xdc, 0xdc, 0xdc),
+    ("ghostwhite", 0xf8, 0xf8, 0xff),
+    ("gold", 0xff, 0xd7, 0x00),
+    ("goldenrod", 0xda, 0xa5, 0x20),
+    ("gray", 0x80, 0x80, 0x80),
+    ("green", 0x00, 0x80, 0x00),
+    ("greenyellow", 0xad, 0xff, 0x2f),
+    ("grey", 0x80, 0x80, 0x80),
+    ("honeydew", 0xf0, 0xff, 0xf0),
+    ("hotpink", 0xff, 0x69, 0xb4),
+    ("indianred", 0xcd, 0x5c, 0x5c),
+    ("indigo", 0x4b, 0x00, 0x82),
+    ("ivory", 0xff, 0xff, 0xf0),
+    ("khaki", 0xf0, 0xe6, 0x8c),
+    ("lavender", 0xe6, 0xe6, 0xfa),
+    ("lavenderblush", 0xff, 0xf0, 0xf5),
+    ("lawngreen", 0x7c, 0xfc, 0x00),
+    ("lemonchiffon", 0xff, 0xfa, 0xcd),
+    ("lightblue", 0xad, 0xd8, 0xe6),
+    ("lightcoral", 0xf0, 0x80, 0x80),
+    ("lightcyan", 0xe0, 0xff, 0xff),
+    ("lightgoldenrodyellow", 0xfa, 0xfa, 0xd2),
+    ("lightgray", 0xd3, 0xd3, 0xd3),
+    ("lightgreen", 0x90, 0xee, 0x90),
+    ("lightgrey", 0xd3, 0xd3, 0xd3),
+    ("lightpink", 0xff, 0xb6, 0xc1),
+    ("lightsalmon", 0xff, 0xa0, 0x7a),
+    ("lightseagreen", 0x20, 0xb2, 0xaa),
+    ("lightskyblue", 0x87, 0xce, 0xfa),
+    ("lightslategray", 0x77, 0x88, 0x99),
+    ("lightslategrey", 0x77, 0x88, 0x99),
+    ("lightsteelblue", 0xb0, 0xc4, 0xde),
+    ("lightyellow", 0xff, 0xff, 0xe0),
+    ("lime", 0x00, 0xff, 0x00),
+    ("limegreen", 0x32, 0xcd, 0x32),
+    ("linen", 0xfa, 0xf0, 0xe6),
+    ("magenta", 0xff, 0x00, 0xff),
+    ("maroon", 0x80, 0x00, 0x00),
+    ("mediumaquamarine", 0x66, 0xcd, 0xaa),
+    ("mediumblue", 0x00, 0x00, 0xcd),
+    ("mediumorchid", 0xba, 0x55, 0xd3),
+    ("mediumpurple", 0x93, 0x70, 0xdb),
+    ("mediumseagreen", 0x3c, 0xb3, 0x71),
+    ("mediumslateblue", 0x7b, 0x68, 0xee),
+    ("mediumspringgreen", 0x00, 0xfa, 0x9a),
+    ("mediumturquoise", 0x48, 0xd1, 0xcc),
+    ("mediumvioletred", 0xc7, 0x15, 0x85),
+    ("midnightblue", 0x19, 0x19, 0x70),
+    ("mintcream", 0xf5, 0xff, 0xfa),
+    ("mistyrose", 0xff, 0xe4, 0xe1),
+    ("moccasin", 0xff, 0xe4, 0xb5),
+    ("navajowhite", 0xff, 0xde, 0xad),
+    ("navy", 0x00, 0x00, 0x80),
+    ("oldlace", 0xfd, 0xf5, 0xe6),
+    ("olive", 0x80, 0x80, 0x00),
+    ("olivedrab", 0x6b, 0x8e, 0x23),
+    ("orange", 0xff, 0xa5, 0x00),
+    ("orangered", 0xff, 0x45, 0x00),
+    ("orchid", 0xda, 0x70, 0xd6),
+    ("palegoldenrod", 0xee, 0xe8, 0xaa),
+    ("palegreen", 0x98, 0xfb, 0x98),
+    ("paleturquoise", 0xaf, 0xee, 0xee),
+    ("palevioletred", 0xdb, 0x70, 0x93),
+    ("papayawhip", 0xff, 0xef, 0xd5),
+    ("peachpuff", 0xff, 0xda, 0xb9),
+    ("peru", 0xcd, 0x85, 0x3f),
+    ("pink", 0xff, 0xc0, 0xcb),
+    ("plum", 0xdd, 0xa0, 0xdd),
+    ("powderblue", 0xb0, 0xe0, 0xe6),
+    ("purple", 0x80, 0x00, 0x80),
+    ("rebeccapurple", 0x66, 0x33, 0x99),
+    ("red", 0xff, 0x00, 0x00),
+    ("rosybrown", 0xbc, 0x8f, 0x8f),
+    ("royalblue", 0x41, 0x69, 0xe1),
+    ("saddlebrown", 0x8b, 0x45, 0x13),
+    ("salmon", 0xfa, 0x80, 0x72),
+    ("sandybrown", 0xf4, 0xa4, 0x60),
+    ("seagreen", 0x2e, 0x8b, 0x57),
+    ("seashell", 0xff, 0xf5, 0xee),
+    ("sienna", 0xa0, 0x52, 0x2d),
+    ("silver", 0xc0, 0xc0, 0xc0),
+    ("skyblue", 0x87, 0xce, 0xeb),
+    ("slateblue", 0x6a, 0x5a, 0xcd),
+    ("slategray", 0x70, 0x80, 0x90),
+    ("slategrey", 0x70, 0x80, 0x90),
+    ("snow", 0xff, 0xfa, 0xfa),
+    ("springgreen", 0x00, 0xff, 0x7f),
+    ("steelblue", 0x46, 0x82, 0xb4),
+    ("tan", 0xd2, 0xb4, 0x8c),
+    ("teal", 0x00, 0x80, 0x80),
+    ("thistle", 0xd8, 0xbf, 0xd8),
+    ("tomato", 0xff, 0x63, 0x47),
+    ("turquoise", 0x40, 0xe0, 0xd0),
+    ("violet", 0xee, 0x82, 0xee),
+    ("wheat", 0xf5, 0xde, 0xb3),
+    ("white", 0xff, 0xff, 0xff),
+    ("whitesmoke", 0xf5, 0xf5, 0xf5),
+    ("yellow", 0xff, 0xff, 0x00),
+    ("yellowgreen", 0x9a, 0xcd, 0x32),
+];
+
+pub enum FlexDirectionValue {
+    Row,
+    RowReverse,
+    Column,
+    ColumnReverse,
+}
+
+impl FlexDirectionValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            FlexDirectionValue::Row => "row",
+            FlexDirectionValue::RowReverse => "row-reverse",
+            FlexDirectionValue::Column => "column",
+            FlexDirectionValue::ColumnReverse => "column-reverse",
+        }
+        .into()
+    }
+}
+
+pub enum FlexWrapValue {
+    Nowrap,
+    Wrap,
+    WrapReverse,
+}
+
+impl FlexWrapValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            FlexWrapValue::Nowrap => "nowrap",
+            FlexWrapValue::Wrap => "wrap",
+            FlexWrapValue::WrapReverse => "wrap-reverse",
+        }
+        .into()
+    }
+}
+
+pub enum JustifyContentValue {
+    Normal,
+    FlexStart,
+    FlexEnd,
+    Start,
+    End,
+    Center,
+    Left,
+    Right,
+    SpaceBetween,
+    SpaceAround,
+    SpaceEvenly,
+    Stretch,
+}
+
+impl JustifyContentValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            JustifyContentValue::Normal => "normal",
+            JustifyContentValue::FlexStart => "flex-start",
+            JustifyContentValue::FlexEnd => "flex-end",
+            JustifyContentValue::Start => "start",
+            JustifyContentValue::End => "end",
+            JustifyContentValue::Center => "center",
+            JustifyContentValue::Left => "left",
+            JustifyContentValue::Right => "right",
+            JustifyContentValue::SpaceBetween => "space-between",
+            JustifyContentValue::SpaceAround => "space-around",
+            JustifyContentValue::SpaceEvenly => "space-evenly",
+            JustifyContentValue::Stretch => "stretch",
+        }
+        .into()
+    }
+}
+
+pub enum AlignItemsValue {
+    Normal,
+    Stretch,
+    FlexStart,
+    FlexEnd,
+    Start,
+    End,
+    Center,
+    SelfStart,
+    SelfEnd,
+    Baseline,
+}
+
+impl AlignItemsValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            AlignItemsValue::Normal => "normal",
+            AlignItemsValue::Stretch => "stretch",
+            AlignItemsValue::FlexStart => "flex-start",
+            AlignItemsValue::FlexEnd => "flex-end",
+            AlignItemsValue::Start => "start",
+            AlignItemsValue::End => "end",
+            AlignItemsValue::Center => "center",
+            AlignItemsValue::SelfStart => "self-start",
+            AlignItemsValue::SelfEnd => "self-end",
+            AlignItemsValue::Baseline => "baseline",
+        }
+        .into()
+    }
+}
+
+pub enum AlignContentValue {
+    Normal,
+    FlexStart,
+    FlexEnd,
+    Start,
+    End,
+    Center,
+    SpaceBetween,
+    SpaceAround,
+    SpaceEvenly,
+    Stretch,
+    Baseline,
+}
+
+impl AlignContentValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            AlignContentValue::Normal => "normal",
+            AlignContentValue::FlexStart => "flex-start",
+            AlignContentValue::FlexEnd => "flex-end",
+            AlignContentValue::Start => "start",
+            AlignContentValue::End => "end",
+            AlignContentValue::Center => "center",
+            AlignContentValue::SpaceBetween => "space-between",
+            AlignContentValue::SpaceAround => "space-around",
+            AlignContentValue::SpaceEvenly => "space-evenly",
+            AlignContentValue::Stretch => "stretch",
+            AlignContentValue::Baseline => "baseline",
+        }
+        .into()
+    }
+}
+
+pub enum AlignSelfValue {
+    Auto,
+    Normal,
+    Stretch,
+    FlexStart,
+    FlexEnd,
+    Start,
+    End,
+    Center,
+    SelfStart,
+    SelfEnd,
+    Baseline,
+}
+
+impl AlignSelfValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            AlignSelfValue::Auto => "auto",
+            AlignSelfValue::Normal => "normal",
+            AlignSelfValue::Stretch => "stretch",
+            AlignSelfValue::FlexStart => "flex-start",
+            AlignSelfValue::FlexEnd => "flex-end",
+            AlignSelfValue::Start => "start",
+            AlignSelfValue::End => "end",
+            AlignSelfValue::Center => "center",
+            AlignSelfValue::SelfStart => "self-start",
+            AlignSelfValue::SelfEnd => "self-end",
+            AlignSelfValue::Baseline => "baseline",
+        }
+        .into()
+    }
+}
+
+/// The `flex-basis` of a flex item, either a size or `content`.
+pub enum FlexBasisValue {
+    Auto,
+    Content,
+    Number(Number),
+}
+
+impl FlexBasisValue {
+    pub fn style_value_helper(&self) -> String {
+        match self {
+            FlexBasisValue::Auto => Auto {}.unit_str(),
+            FlexBasisValue::Content => "content".into(),
+            FlexBasisValue::Number(n) => n.style_value_helper(),
+        }
+    }
+}
+
+impl From<Number> for FlexBasisValue {
+    fn from(n: Number) -> Self {
+        FlexBasisValue::Number(n)
+    }
+}
+
+/// The size of a single grid track.
+pub enum TrackSize {
+    Number(Number),
+    Flex(Flex),
+    Auto,
+    MinContent,
+    MaxContent,
+    MinMax(Box<TrackSize>, Box<TrackSize>),
+    FitContent(Number),
+}
+
+impl TrackSize {
+    pub fn minmax<A: Into<TrackSize>, B: Into<TrackSize>>(min: A, max: B) -> Self {
+        TrackSize::MinMax(Box::new(min.into()), Box::new(max.into()))
+    }
+
+    pub fn style_value_helper(&self) -> String {
+        match self {
+            TrackSize::Number(n) => n.style_value_helper(),
+            TrackSize::Flex(f) => f.style_value_helper(),
+            TrackSize::Auto => Auto {}.unit_str(),
+            TrackSize::MinContent => "min-content".into(),
+            TrackSize::MaxContent => "max-content".into(),
+            TrackSize::MinMax(min, max) => format!(
+                "minmax({}, {})",
+                min.style_value_helper(),
+                max.style_value_helper()
+            ),
+            TrackSize::FitContent(n) => format!("fit-content({})", n.style_value_helper()),
+        }
+    }
+}
+
+impl From<Number> for TrackSize {
+    fn from(n: Number) -> Self {
+        TrackSize::Number(n)
+    }
+}
+
+impl From<Flex> for TrackSize {
+    fn from(f: Flex) -> Self {
+        TrackSize::Flex(f)
+    }
+}
+
+pub enum RepeatCount {
+    Count(std::num::NonZeroU32),
+    AutoFill,
+    AutoFit,
+}
+
+impl RepeatCount {
+    pub fn value_string(&self) -> String {
+        match self {
+            RepeatCount::Count(c) => c.to_string(),
+            RepeatCount::AutoFill => "auto-fill".into(),
+            RepeatCount::AutoFit => "auto-fit".into(),
+        }
+    }
+}
+
+/// One entry of a `grid-template-columns` or `grid-template-rows` track list.
+pub enum TrackListItem {
+    Size(TrackSize),
+    /// Names for the grid line at this point, rendered as `[name ...]`.
+    LineNames(Vec<GridIdent>),
+    Repeat(RepeatCount, Vec<TrackListItem>),
+}
+
+impl TrackListItem {
+    pub fn style_value_helper(&self) -> String {
+        match self {
+            TrackListItem::Size(s) => s.style_value_helper(),
+            TrackListItem::LineNames(names) => format!(
+                "[{}]",
+                names
+                    .iter()
+                    .map(|n| n.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(" ")
+            ),
+            TrackListItem::Repeat(count, items) => format!(
+                "repeat({}, {})",
+                count.value_string(),
+                track_list_string(items)
+            ),
+        }
+    }
+}
+
+impl<T: Into<TrackSize>> From<T> for TrackListItem {
+    fn from(s: T) -> Self {
+        TrackListItem::Size(s.into())
+    }
+}
+
+pub fn track_list_string(items: &[TrackListItem]) -> String {
+    items
+        .iter()
+        .map(|i| i.style_value_helper())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// The cells of a `grid-template-areas` value, one name per cell and `.` for an empty
+/// cell. Building it checks that the rows line up and that every area is a rectangle.
+pub struct GridTemplateAreas {
+    rows: Vec<Vec<String>>,
+}
+
+impl GridTemplateAreas {
+    pub fn new<S: Into<String>>(rows: Vec<Vec<S>>) -> Result<Self, String> {
+        let rows: Vec<Vec<String>> = rows
+            .into_iter()
+            .map(|r| r.into_iter().map(|c| c.into()).collect())
+            .collect();
+        let width = match rows.first() {
+            Some(r) if !r.is_empty() => r.len(),
+            _ => return Err("grid template areas need at least one cell".into()),
+        };
+        if rows.iter().any(|r| r.len() != width) {
+            return Err("every row of grid template areas must have the same length".into());
+        }
+        let mut checked: Vec<&str> = Vec::new();
+        for (y, row) in rows.iter().enumerate() {
+            for (x, name) in row.iter().enumerate() {
+                if name == "." || checked.contains(&name.as_str()) {
+                    continue;
+                }
+                GridIdent::new(name.as_str())?;
+                // The first cell found is the top left corner, so the area must fill the
+                // rectangle out to its furthest cells and appear nowhere else
+                let cells: Vec<(usize, usize)> = rows
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(cy, r)| {
+                        r.iter()
+                            .enumerate()
+                            .filter(|(_, c)| *c == name)
+                            .map(move |(cx, _)| (cx, cy))
+                    })
+                    .collect();
+                let right = cells.iter().map(|c| c.0).max().unwrap_or(x);
+                let bottom = cells.iter().map(|c| c.1).max().unwrap_or(y);
+                let left = cells.iter().map(|c| c.0).min().unwrap_or(x);
+                if left != x || cells.len() != (right - x + 1) * (bottom - y + 1) {
+                    return Err(format!("grid area {:?} is not a rectangle", name));
+                }
+                checked.push(name);
+            }
+        }
+        Ok(GridTemplateAreas { rows })
+    }
+
+    pub fn style_value_helper(&self) -> String {
+        self.rows
+            .iter()
+            .map(|r| format!("\"{}\"", r.join(" ")))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+}
+
+fn is_grid_ident(name: &str) -> bool {
+    let start = name.strip_prefix('-').unwrap_or(name);
+    !start.is_empty()
+        && !start.starts_with(|c: char| c.is_ascii_digit() || c == '-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || !c.is_ascii())
+}
+
+/// The name of a grid line or area, built with `GridIdent::new` which checks that it is a css
+/// identifier, and not one of the keywords it would be confused with.
+pub struct GridIdent(String);
+
+impl GridIdent {
+    pub fn new<S: Into<String>>(name: S) -> Result<Self, String> {
+        let name = name.into();
+        if !is_grid_ident(&name) {
+            return Err(format!("{:?} is not a valid grid name", name));
+        }
+        if GRID_RESERVED_NAMES
+            .iter()
+            .any(|k| k.eq_ignore_ascii_case(&name))
+        {
+            return Err(format!("{:?} is a keyword, not a grid name", name));
+        }
+        Ok(GridIdent(name))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+const GRID_RESERVED_NAMES: &[&str] = &[
+    "auto",
+    "span",
+    "default",
+    "inherit",
+    "initial",
+    "unset",
+    "revert",
+    "revert-layer",
+];
+
+/// A grid line, used as the start or end of `grid-row`, `grid-column` and `grid-area`.
+pub enum GridLineValue {
+    Auto,
+    Line(std::num::NonZeroI32),
+    Named(GridIdent),
+    NamedLine(GridIdent, std::num::NonZeroI32),
+    Span(std::num::NonZeroU32),
+    SpanNamed(GridIdent),
+}
+
+impl GridLineValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            GridLineValue::Auto => Auto {}.unit_str(),
+            GridLineValue::Line(l) => l.to_string(),
+            GridLineValue::Named(n) => n.as_str().into(),
+            GridLineValue::NamedLine(n, l) => format!("{} {}", l, n.as_str()),
+            GridLineValue::Span(s) => format!("span {}", s),
+            GridLineValue::SpanNamed(n) => format!("span {}", n.as_str()),
+        }
+    }
+}
+
+pub enum GridAutoFlowValue {
+    Row,
+    Column,
+    Dense,
+    RowDense,
+    ColumnDense,
+}
+
+impl GridAutoFlowValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            GridAutoFlowValue::Row => "row",
+            GridAutoFlowValue::Column => "column",
+            GridAutoFlowValue::Dense => "dense",
+            GridAutoFlowValue::RowDense => "row dense",
+            GridAutoFlowValue::ColumnDense => "column dense",
+        }
+        .into()
+    }
+}
+
+pub enum JustifyItemsValue {
+    Normal,
+    Stretch,
+    Start,
+    End,
+    Center,
+    Left,
+    Right,
+    SelfStart,
+    SelfEnd,
+    Baseline,
+    Legacy,
+}
+
+impl JustifyItemsValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            JustifyItemsValue::Normal => "normal",
+            JustifyItemsValue::Stretch => "stretch",
+            JustifyItemsValue::Start => "start",
+            JustifyItemsValue::End => "end",
+            JustifyItemsValue::Center => "center",
+            JustifyItemsValue::Left => "left",
+            JustifyItemsValue::Right => "right",
+            JustifyItemsValue::SelfStart => "self-start",
+            JustifyItemsValue::SelfEnd => "self-end",
+            JustifyItemsValue::Baseline => "baseline",
+            JustifyItemsValue::Legacy => "legacy",
+        }
+        .into()
+    }
+}
+
+pub enum JustifySelfValue {
+    Auto,
+    Normal,
+    Stretch,
+    Start,
+    End,
+    Center,
+    Left,
+    Right,
+    SelfStart,
+    SelfEnd,
+    Baseline,
+}
+
+impl JustifySelfValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            JustifySelfValue::Auto => "auto",
+            JustifySelfValue::Normal => "normal",
+            JustifySelfValue::Stretch => "stretch",
+            JustifySelfValue::Start => "start",
+            JustifySelfValue::End => "end",
+            JustifySelfValue::Center => "center",
+            JustifySelfValue::Left => "left",
+            JustifySelfValue::Right => "right",
+            JustifySelfValue::SelfStart => "self-start",
+            JustifySelfValue::SelfEnd => "self-end",
+            JustifySelfValue::Baseline => "baseline",
+        }
+        .into()
+    }
+}
+
+/// A generic font family, which a font family list always ends with.
+pub enum GenericFontFamily {
+    Serif,
+    SansSerif,
+    Monospace,
+    Cursive,
+    Fantasy,
+    SystemUi,
+    UiSerif,
+    UiSansSerif,
+    UiMonospace,
+    UiRounded,
+    Math,
+    Emoji,
+    Fangsong,
+}
+
+impl GenericFontFamily {
+    pub fn value_string(&self) -> String {
+        match self {
+            GenericFontFamily::Serif => "serif",
+            GenericFontFamily::SansSerif => "sans-serif",
+            GenericFontFamily::Monospace => "monospace",
+            GenericFontFamily::Cursive => "cursive",
+            GenericFontFamily::Fantasy => "fantasy",
+            GenericFontFamily::SystemUi => "system-ui",
+            GenericFontFamily::UiSerif => "ui-serif",
+            GenericFontFamily::UiSansSerif => "ui-sans-serif",
+            GenericFontFamily::UiMonospace => "ui-monospace",
+            GenericFontFamily::UiRounded => "ui-rounded",
+            GenericFontFamily::Math => "math",
+            GenericFontFamily::Emoji => "emoji",
+            GenericFontFamily::Fangsong => "fangsong",
+        }
+        .into()
+    }
+}
+
+pub enum FontSizeValue {
+    XxSmall,
+    XSmall,
+    Small,
+    Medium,
+    Large,
+    XLarge,
+    XxLarge,
+    XxxLarge,
+    Smaller,
+    Larger,
+    Number(Number),
+}
+
+impl FontSizeValue {
+    pub fn style_value_helper(&self) -> String {
+        match self {
+            FontSizeValue::XxSmall => "xx-small".into(),
+            FontSizeValue::XSmall => "x-small".into(),
+            FontSizeValue::Small => "small".into(),
+            FontSizeValue::Medium => "medium".into(),
+            FontSizeValue::Large => "large".into(),
+            FontSizeValue::XLarge => "x-large".into(),
+            FontSizeValue::XxLarge => "xx-large".into(),
+            FontSizeValue::XxxLarge => "xxx-large".into(),
+            FontSizeValue::Smaller => "smaller".into(),
+            FontSizeValue::Larger => "larger".into(),
+            FontSizeValue::Number(n) => n.style_value_helper(),
+        }
+    }
+}
+
+impl From<Number> for FontSizeValue {
+    fn from(n: Number) -> Self {
+        FontSizeValue::Number(n)
+    }
+}
+
+pub enum FontWeightValue {
+    Normal,
+    Bold,
+    Bolder,
+    Lighter,
+    /// A numeric weight from 1 to 1000, clamped when rendered.
+    Weight(u16),
+}
+
+impl FontWeightValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            FontWeightValue::Normal => "normal".into(),
+            FontWeightValue::Bold => "bold".into(),
+            FontWeightValue::Bolder => "bolder".into(),
+            FontWeightValue::Lighter => "lighter".into(),
+            FontWeightValue::Weight(w) => (*w).clamp(1, 1000).to_string(),
+        }
+    }
+}
+
+pub enum FontStyleValue {
+    Normal,
+    Italic,
+    Oblique,
+}
+
+impl FontStyleValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            FontStyleValue::Normal => "normal",
+            FontStyleValue::Italic => "italic",
+            FontStyleValue::Oblique => "oblique",
+        }
+        .into()
+    }
+}
+
+pub enum LineHeightValue {
+    Normal,
+    /// A multiple of the font size, like `1.5`.
+    Multiplier(Finite),
+    Number(Number),
+}
+
+impl LineHeightValue {
+    pub fn style_value_helper(&self) -> String {
+        match self {
+            LineHeightValue::Normal => "normal".into(),
+            LineHeightValue::Multiplier(m) => m.value_string(),
+            LineHeightValue::Number(n) => n.style_value_helper(),
+        }
+    }
+}
+
+impl From<Number> for LineHeightValue {
+    fn from(n: Number) -> Self {
+        LineHeightValue::Number(n)
+    }
+}
+
+pub enum SpacingValue {
+    Normal,
+    Number(Number),
+}
+
+impl SpacingValue {
+    pub fn style_value_helper(&self) -> String {
+        match self {
+            SpacingValue::Normal => "normal".into(),
+            SpacingValue::Number(n) => n.style_value_helper(),
+        }
+    }
+}
+
+impl From<Number> for SpacingValue {
+    fn from(n: Number) -> Self {
+        SpacingValue::Number(n)
+    }
+}
+
+pub enum TextAlignValue {
+    Start,
+    End,
+    Left,
+    Right,
+    Center,
+    Justify,
+    MatchParent,
+}
+
+impl TextAlignValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            TextAlignValue::Start => "start",
+            TextAlignValue::End => "end",
+            TextAlignValue::Left => "left",
+            TextAlignValue::Right => "right",
+            TextAlignValue::Center => "center",
+            TextAlignValue::Justify => "justify",
+            TextAlignValue::MatchParent => "match-parent",
+        }
+        .into()
+    }
+}
+
+pub enum TextDecorationLineValue {
+    Underline,
+    Overline,
+    LineThrough,
+}
+
+impl TextDecorationLineValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            TextDecorationLineValue::Underline => "underline",
+            TextDecorationLineValue::Overline => "overline",
+            TextDecorationLineValue::LineThrough => "line-through",
+        }
+        .into()
+    }
+}
+
+pub enum TextDecorationStyleValue {
+    Solid,
+    Double,
+    Dotted,
+    Dashed,
+    Wavy,
+}
+
+impl TextDecorationStyleValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            TextDecorationStyleValue::Solid => "solid",
+            TextDecorationStyleValue::Double => "double",
+            TextDecorationStyleValue::Dotted => "dotted",
+            TextDecorationStyleValue::Dashed => "dashed",
+            TextDecorationStyleValue::Wavy => "wavy",
+        }
+        .into()
+    }
+}
+
+pub enum TextTransformValue {
+    None,
+    Capitalize,
+    Uppercase,
+    Lowercase,
+    FullWidth,
+}
+
+impl TextTransformValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            TextTransformValue::None => "none",
+            TextTransformValue::Capitalize => "capitalize",
+            TextTransformValue::Uppercase => "uppercase",
+            TextTransformValue::Lowercase => "lowercase",
+            TextTransformValue::FullWidth => "full-width",
+        }
+        .into()
+    }
+}
+
+pub enum WhiteSpaceValue {
+    Normal,
+    Nowrap,
+    Pre,
+    PreWrap,
+    PreLine,
+    BreakSpaces,
+}
+
+impl WhiteSpaceValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            WhiteSpaceValue::Normal => "normal",
+            WhiteSpaceValue::Nowrap => "nowrap",
+            WhiteSpaceValue::Pre => "pre",
+            WhiteSpaceValue::PreWrap => "pre-wrap",
+            WhiteSpaceValue::PreLine => "pre-line",
+            WhiteSpaceValue::BreakSpaces => "break-spaces",
+        }
+        .into()
+    }
+}
+
+pub enum WordBreakValue {
+    Normal,
+    BreakAll,
+    KeepAll,
+}
+
+impl WordBreakValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            WordBreakValue::Normal => "normal",
+            WordBreakValue::BreakAll => "break-all",
+            WordBreakValue::KeepAll => "keep-all",
+        }
+        .into()
+    }
+}
+
+pub enum OverflowWrapValue {
+    Normal,
+    BreakWord,
+    Anywhere,
+}
+
+impl OverflowWrapValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            OverflowWrapValue::Normal => "normal",
+            OverflowWrapValue::BreakWord => "break-word",
+            OverflowWrapValue::Anywhere => "anywhere",
+        }
+        .into()
+    }
+}
+
+pub enum TextOverflowValue {
+    Clip,
+    Ellipsis,
+}
+
+impl TextOverflowValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            TextOverflowValue::Clip => "clip",
+            TextOverflowValue::Ellipsis => "ellipsis",
+        }
+        .into()
+    }
+}
+
+pub enum NumericFigureValue {
+    LiningNums,
+    OldstyleNums,
+}
+
+impl NumericFigureValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            NumericFigureValue::LiningNums => "lining-nums",
+            NumericFigureValue::OldstyleNums => "oldstyle-nums",
+        }
+        .into()
+    }
+}
+
+pub enum NumericSpacingValue {
+    ProportionalNums,
+    TabularNums,
+}
+
+impl NumericSpacingValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            NumericSpacingValue::ProportionalNums => "proportional-nums",
+            NumericSpacingValue::TabularNums => "tabular-nums",
+        }
+        .into()
+    }
+}
+
+pub enum NumericFractionValue {
+    DiagonalFractions,
+    StackedFractions,
+}
+
+impl NumericFractionValue {
+    pub fn value_string(&self) -> String {
+        match self {
+            NumericFractionValue::DiagonalFractions => "diagonal-fractions",
+            NumericFractionValue::StackedFractions => "stacked-fractions",
+        }
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canonical(s: &str) -> String {
+        LanguageTag::parse(s)
+            .unwrap_or_else(|e| panic!("{}", e))
+            .lang_tag_str()
+    }
+
+    #[test]
+    fn numbers_must_be_finite() {
+        for v in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert!(Finite::new(v).is_err());
+        }
+        assert_eq!(px(-4).style_value_helper(), "-4px");
+        assert_eq!(
+            rem(Finite::new(-1.5).unwrap()).style_value_helper(),
+            "-1.5rem"
+        );
+        assert_eq!(percent(100).style_value_helper(), "100%");
+        assert_eq!(fr(1).style_value_helper(), "1fr");
+        let half = MathExpr::length(percent(100))
+            .divided_by(MathExpr::scalar(2))
+            .unwrap();
+        assert_eq!(half.style_value_helper(), "calc(100% / 2)");
+    }
+
+    #[test]
+    fn math_dimensions_are_checked() {
+        let length = || MathExpr::length(px(2));
+        assert!(length().times(length()).is_err());
+        assert!(length().plus(MathExpr::scalar(1)).is_err());
+        assert!(MathExpr::scalar(1).minus(length()).is_err());
+        assert!(MathExpr::scalar(1).divided_by(length()).is_err());
+        assert!(length().divided_by(MathExpr::scalar(0)).is_err());
+        assert!(MathExpr::min(vec![]).is_err());
+        assert!(MathExpr::max(vec![length(), MathExpr::scalar(1)]).is_err());
+        assert!(MathExpr::scalar(3).times(length()).is_ok());
+    }
+
+    #[test]
+    fn math_functions_are_rendered() {
+        let min = MathExpr::min(vec![MathExpr::length(percent(100)), px(600).into()]).unwrap();
+        assert_eq!(min.style_value_helper(), "min(100%, 600px)");
+        let max = MathExpr::max(vec![
+            MathExpr::length(vw(50)).minus(rem(2).into()).unwrap(),
+            px(300).into(),
+        ])
+        .unwrap();
+        assert_eq!(max.style_value_helper(), "max(50vw - 2rem, 300px)");
+        let clamp = MathExpr::clamp(
+            rem(1).into(),
+            MathExpr::length(vw(Finite::new(2.5).unwrap())),
+            rem(2).into(),
+        )
+        .unwrap();
+        assert_eq!(clamp.style_value_helper(), "clamp(1rem, 2.5vw, 2rem)");
+        let width = MathExpr::length(percent(100)).minus(rem(2).into()).unwrap();
+        assert_eq!(width.style_value_helper(), "calc(100% - 2rem)");
+    }
+
+    #[test]
+    fn math_sums_are_parenthesised_under_products() {
+        let sum = || MathExpr::length(px(1)).plus(em(2).into()).unwrap();
+        assert_eq!(
+            sum()
+                .times(MathExpr::scalar(3))
+                .unwrap()
+                .style_value_helper(),
+            "calc((1px + 2em) * 3)"
+        );
+        assert_eq!(
+            MathExpr::scalar(3)
+                .times(sum())
+                .unwrap()
+                .style_value_helper(),
+            "calc(3 * (1px + 2em))"
+        );
+        assert_eq!(
+            sum()
+                .divided_by(MathExpr::scalar(2).minus(MathExpr::scalar(1)).unwrap())
+                .unwrap()
+                .style_value_helper(),
+            "calc((1px + 2em) / (2 - 1))"
+        );
+        // A sum on the right of a minus keeps its parentheses too
+        assert_eq!(
+            MathExpr::length(px(1))
+                .minus(sum())
+                .unwrap()
+                .style_value_helper(),
+            "calc(1px - (1px + 2em))"
+        );
+    }
+
+    #[test]
+    fn grid_names_must_be_identifiers() {
+        for name in ["main", "sidebar-2", "_x", "caf\u{e9}", "-a"] {
+            assert_eq!(GridIdent::new(name).unwrap().as_str(), name);
+        }
+        for name in [
+            "",
+            "a;b",
+            "a}b",
+            "x; color: red",
+            "a b",
+            "1st",
+            "--var",
+            "[a]",
+            "span",
+            "AUTO",
+            "inherit",
+        ] {
+            assert!(GridIdent::new(name).is_err(), "{:?}", name);
+        }
+        let names = TrackListItem::LineNames(vec![
+            GridIdent::new("a").unwrap(),
+            GridIdent::new("b").unwrap(),
+        ]);
+        assert_eq!(names.style_value_helper(), "[a b]");
+        let line = GridLineValue::NamedLine(
+            GridIdent::new("col").unwrap(),
+            std::num::NonZeroI32::new(-1).unwrap(),
+        );
+        assert_eq!(line.value_string(), "-1 col");
+        let span = GridLineValue::Span(std::num::NonZeroU32::new(2).unwrap());
+        assert_eq!(span.value_string(), "span 2");
+    }
+
+    #[test]
+    fn grid_template_areas_must_be_rectangles() {
+        let areas = GridTemplateAreas::new(vec![
+            vec!["head", "head", "head"],
+            vec!["side", "main", "main"],
+            vec!["side", "main", "main"],
+            vec![".", "foot", "."],
+        ])
+        .unwrap();
+        assert_eq!(
+            areas.style_value_helper(),
+            "\"head head head\" \"side main main\" \"side main main\" \". foot .\""
+        );
+        let invalid: Vec<Vec<Vec<&str>>> = vec![
+            // An L shape
+            vec![vec!["a", "a"], vec!["a", "b"]],
+            // Split in two
+            vec![vec!["a", "b", "a"]],
+            vec![vec!["a"], vec!["b"], vec!["a"]],
+            // Filling the rectangle's cell count, but not its cells
+            vec![vec!["a", "b"], vec!["b", "a"]],
+            // Ragged rows
+            vec![vec!["a", "a"], vec!["a"]],
+            vec![],
+            vec![vec![]],
+            // Names which are not identifiers, or are keywords
+            vec![vec!["1st"]],
+            vec![vec!["a b"]],
+            vec![vec!["span"]],
+            vec![vec!["Auto"]],
+        ];
+        for rows in invalid {
+            assert!(GridTemplateAreas::new(rows.clone()).is_err(), "{:?}", rows);
+        }
+    }
+
+    #[test]
+    fn colors_reject_non_finite_numbers() {
+        let cases = [
+            "rgb(nan, 0, 0)",
+            "rgb(inf, 0, 0)",
+            "rgb(0 0 0 / NaN)",
+            "rgba(0, 0, 0, infinity)",
+            "rgb(-inf%, 0, 0)",
+            "hsl(nan, 50%, 50%)",
+            "hsl(0, inf%, 50%)",
+            "hsla(0, 50%, 50%, -infinity)",
+            "hsl(1e999, 50%, 50%)",
+        ];
+        for color in cases.iter() {
+            assert!(ColorValue::parse(color).is_err(), "{}", color);
+        }
+        assert_eq!(
+            ColorValue::parse("rgb(300, 0, 50%)")
+                .unwrap()
+                .value_string(),
+            "rgb(255, 0, 128)"
+        );
+    }
+
+    #[test]
+    fn with_alpha_rounds() {
+        let cases = [
+            (ColorValue::hex(0x336699), 0.5, "#33669980"),
+            (ColorValue::hex(0x336699), 0.999, "#336699"),
+            (ColorValue::rgb(1, 2, 3), 0.25, "rgba(1, 2, 3, 0.25)"),
+            (
+                ColorValue::Named(NamedColor::from_name("rebeccapurple").unwrap()),
+                2.0,
+                "rgb(102, 51, 153)",
+            ),
+            (
+                ColorValue::hsl(0.0, 100.0, 30.0).unwrap(),
+                0.5,
+                "hsla(0, 100%, 30%, 0.5)",
+            ),
+        ];
+        for (color, alpha, expected) in cases.iter() {
+            assert_eq!(color.with_alpha(*alpha).unwrap().value_string(), *expected);
+        }
+        assert!(ColorValue::rgb(1, 2, 3).with_alpha(f64::NAN).is_none());
+    }
+
+    #[test]
+    fn colors_are_adjusted() {
+        let blue = ColorValue::hex(0x336699);
+        assert_eq!(
+            blue.lighten(20.0).unwrap().value_string(),
+            "hsl(210, 50%, 60%)"
+        );
+        assert_eq!(
+            blue.darken(50.0).unwrap().value_string(),
+            "hsl(210, 50%, 0%)"
+        );
+        assert!(ColorValue::CurrentColor.lighten(10.0).is_none());
+
+        let black = ColorValue::rgb(0, 0, 0);
+        let white = ColorValue::rgb(255, 255, 255);
+        assert_eq!(
+            black.mix(&white, 0.25).unwrap().value_string(),
+            "rgb(191, 191, 191)"
+        );
+        assert_eq!(
+            black
+                .mix(&ColorValue::Transparent, 0.5)
+                .unwrap()
+                .value_string(),
+            "rgba(0, 0, 0, 0.5)"
+        );
+
+        assert_eq!(black.contrast_ratio(&white), Some(21.0));
+        assert_eq!(white.contrast_ratio(&white), Some(1.0));
+        let ratio = blue.contrast_ratio(&white).unwrap();
+        assert!((ratio - 5.998).abs() < 0.001, "{}", ratio);
+        assert!(blue.contrast_ratio(&ColorValue::CurrentColor).is_none());
+    }
+
+    #[test]
+    fn colors_reject_non_finite_adjustments() {
+        let blue = ColorValue::hex(0x336699);
+        for amount in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert!(blue.lighten(amount).is_none());
+            assert!(blue.darken(amount).is_none());
+            assert!(ColorValue::hsl(amount, 50.0, 50.0).is_err());
+            assert!(ColorValue::hsl(0.0, amount, 50.0).is_err());
+            assert!(ColorValue::hsl(0.0, 50.0, amount).is_err());
+        }
+        assert!(blue.mix(&ColorValue::rgb(0, 0, 0), f64::NAN).is_none());
+        assert_eq!(
+            blue.mix(&ColorValue::rgb(0, 0, 0), f64::INFINITY)
+                .unwrap()
+                .value_string(),
+            "rgb(51, 102, 153)"
+        );
+    }
+
+    #[test]
+    fn language_tags_are_canonicalized() {
+        let cases = [
+            ("EN", "en"),
+            ("en-us", "en-US"),
+            ("SR-latn-rs", "sr-Latn-RS"),
+            ("zh-HANT-tw", "zh-Hant-TW"),
+            ("es-419", "es-419"),
+            ("zh-YUE-hk", "zh-yue-HK"),
+            ("ar-AAO", "ar-aao"),
+            ("sl-ROZAJ-biske-1994", "sl-rozaj-biske-1994"),
+            ("de-CH-1901", "de-CH-1901"),
+            ("en-US-U-CA-gregory-t-ja", "en-US-u-ca-gregory-t-ja"),
+            ("en-a-bbb-x-a-ccc", "en-a-bbb-x-a-ccc"),
+            ("X-Whatever", "x-whatever"),
+            ("qaa-Qaaa-QM-x-southern", "qaa-Qaaa-QM-x-southern"),
+        ];
+        for (tag, expected) in cases.iter() {
+            assert_eq!(canonical(tag), *expected, "{}", tag);
+        }
+    }
+
+    #[test]
+    fn language_tag_subtags() {
+        let tag = LanguageTag::parse("zh-cmn-Hans-CN-variant1-u-co-pinyin-x-private").unwrap();
+        assert_eq!(tag.language(), "zh");
+        assert_eq!(tag.script(), Some("Hans"));
+        assert_eq!(tag.region(), Some("CN"));
+        assert_eq!(tag.variants(), ["variant1"]);
+        assert!(!tag.is_grandfathered());
+    }
+
+    #[test]
+    fn grandfathered_language_tags() {
+        let cases = [
+            ("i-klingon", "i-klingon"),
+            ("I-KLINGON", "i-klingon"),
+            ("en-gb-oed", "en-GB-oed"),
+            ("sgn-be-fr", "sgn-BE-FR"),
+            ("zh-min-nan", "zh-min-nan"),
+            ("art-lojban", "art-lojban"),
+        ];
+        for (tag, expected) in cases.iter() {
+            assert_eq!(canonical(tag), *expected, "{}", tag);
+            assert!(
+                LanguageTag::parse(tag).unwrap().is_grandfathered(),
+                "{}",
+                tag
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_language_tags() {
+        let cases = [
+            "",
+            "e",
+            "i-notatag",
+            "en-",
+            "-en",
+            "en--US",
+            "toolonglanguage",
+            "abcd",
+            "en-US-US",
+            "de-1901-1901",
+            "en-a",
+            "en-a-bbb-a-ccc",
+            "en-x",
+            "x",
+            "en_US",
+            "en-\u{e9}",
+        ];
+        for tag in cases.iter() {
+            assert!(LanguageTag::parse(tag).is_err(), "{}", tag);
+        }
+    }
+
+    #[test]
+    fn checked_sources_reject_dangerous_schemes() {
+        for url in [
+            "javascript:alert(1)",
+            " JavaScript:x",
+            "java\tscript:x",
+            "data:text/html,x",
+        ] {
+            assert!(SourceValue::try_new(url.into()).is_err(), "{:?}", url);
+            assert!(
+                SourceCheck::new()
+                    .allow_dangerous_scheme()
+                    .try_new(url.into())
+                    .is_ok(),
+                "{:?}",
+                url
+            );
+        }
+        for url in ["https://example.com", "/a:b", "a/b", "mailto:a@example.com"] {
+            assert!(SourceValue::try_new(url.into()).is_ok(), "{:?}", url);
+        }
+        // `new` is left unchecked
+        assert_eq!(
+            SourceValue::new("javascript:x".into()).source_value_str(),
+            "javascript:x"
+        );
+    }
+
+    #[test]
+    fn checked_urls_are_validated() {
+        let url = Url::absolute("https", "Example.com").with_segment("a b");
+        assert_eq!(
+            SourceValue::from_url(url).unwrap().source_value_str(),
+            "https://example.com/a%20b"
+        );
+        assert!(SourceValue::from_url(Url::absolute("javascript", "x")).is_err());
+        assert!(SourceValue::from_url(Url::absolute("https", "b\u{fc}cher.example")).is_err());
+        assert!(SourceValue::from_url(Url::absolute("https", "xn--bcher-kva.example")).is_ok());
+        assert!(SourceValue::from_url(Url::absolute("https", "a/b")).is_err());
+    }
+
+    #[test]
+    fn empty_url_segments_are_rejected() {
+        let cases = [
+            (
+                Url::root().with_segments(vec!["", "evil.com", "x"]),
+                "//evil.com/x",
+            ),
+            (
+                Url::relative().with_segments(vec!["", "evil.com"]),
+                "/evil.com",
+            ),
+            (
+                Url::absolute("https", "a.com").with_segments(vec!["", "b"]),
+                "https://a.com//b",
+            ),
+        ];
+        for (url, rendered) in cases.iter() {
+            assert_eq!(url.url_string(), *rendered);
+            assert!(url.validate().is_err(), "{:?}", rendered);
+        }
+        assert!(Url::root().with_segment("a").validate().is_ok());
     }
 }
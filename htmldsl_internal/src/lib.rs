@@ -1,30 +1,114 @@
 pub mod element_traits {
+    use std::borrow::Cow;
+    use std::collections::HashSet;
+    use std::fmt;
+    use std::io;
+
     use super::attr_traits;
     use super::style_traits;
 
-    pub enum Element {
-        Tag(Box<dyn GenericRenderable + 'static>),
-        Text(String),
+    use super::escape;
+
+    /// An owned (or borrowed, for `'a`) node in the element tree.
+    pub enum Element<'a> {
+        Tag(Box<dyn GenericRenderable + 'a>),
+        Text(Cow<'a, str>),
+        Raw(Cow<'a, str>),
+        Comment(Cow<'a, str>),
+        ConditionalComment {
+            condition: Cow<'a, str>,
+            children: Vec<Element<'a>>,
+        },
+        Fragment(Vec<Element<'a>>),
     }
 
-    pub fn tag(r: Box<dyn GenericRenderable>) -> Element {
+    // Element trees must be able to cross threads, such as across `.await` points in a web
+    // handler
+    const _: fn() = || {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Element>();
+        assert_send_sync::<Renderable>();
+    };
+
+    pub fn tag<'a>(r: Box<dyn GenericRenderable + 'a>) -> Element<'a> {
         Element::Tag(r)
     }
 
-    pub fn text<S: Into<String>>(s: S) -> Element {
+    /// Text content which is HTML escaped when rendered.
+    pub fn text<'a, S: Into<Cow<'a, str>>>(s: S) -> Element<'a> {
         Element::Text(s.into())
     }
 
-    impl<'a> Element {
-        pub fn into_renderable(&self) -> Renderable {
+    /// Markup which is rendered verbatim, without any escaping.
+    ///
+    /// Only use this for markup which is fully trusted; any user supplied content in here can
+    /// break the page or inject script.
+    pub fn dangerously_trusted_html<'a, S: Into<Cow<'a, str>>>(s: S) -> Element<'a> {
+        Element::Raw(s.into())
+    }
+
+    /// An html comment, any `--` in the text is broken up so it can not end the comment early.
+    pub fn comment<'a, S: Into<Cow<'a, str>>>(s: S) -> Element<'a> {
+        Element::Comment(s.into())
+    }
+
+    /// Markup which only old versions of internet explorer read, wrapped as
+    /// `<!--[if condition]>...<![endif]-->` so that every other browser skips it as a comment.
+    ///
+    /// The condition may only hold the characters of the conditional comment grammar, like
+    /// `lt IE 9` or `(gte IE 6)&!(IE 8)`. Plain comments in the children are dropped when
+    /// rendered, as their end would also end the conditional comment.
+    pub fn conditional_comment<'a, S: Into<Cow<'a, str>>>(
+        condition: S,
+        children: Vec<Element<'a>>,
+    ) -> Result<Element<'a>, String> {
+        let condition = condition.into();
+        if condition.trim().is_empty() {
+            return Err("conditional comments need a condition".into());
+        }
+        if let Some(c) = condition
+            .chars()
+            .find(|c| !c.is_ascii_alphanumeric() && !" !()&|.".contains(*c))
+        {
+            return Err(format!(
+                "'{}' can not be used in the condition '{}'",
+                c, condition
+            ));
+        }
+        Ok(Element::ConditionalComment {
+            condition,
+            children,
+        })
+    }
+
+    /// Sibling elements without any wrapping tag, when given as a child its elements are
+    /// spliced into the parent's children.
+    pub fn fragment(children: Vec<Element>) -> Element {
+        Element::Fragment(children)
+    }
+
+    impl<'a> Element<'a> {
+        pub fn into_renderable(&self) -> Renderable<'_> {
             match self {
                 Element::Tag(ref ge) => Renderable::Tag((**ge).as_tag_renderable()),
-                Element::Text(ref t) => Renderable::Text(t.clone()),
+                Element::Text(ref t) => Renderable::Text(Cow::Borrowed(t)),
+                Element::Raw(ref r) => Renderable::Raw(Cow::Borrowed(r)),
+                Element::Comment(ref c) => Renderable::Comment(Cow::Borrowed(c)),
+                Element::ConditionalComment {
+                    ref condition,
+                    ref children,
+                } => Renderable::ConditionalComment {
+                    condition: Cow::Borrowed(condition),
+                    children: children.iter().map(|e| e.into_renderable()).collect(),
+                },
+                Element::Fragment(ref f) => {
+                    Renderable::Fragment(f.iter().map(|e| e.into_renderable()).collect())
+                }
             }
         }
     }
 
-    pub trait GenericRenderable: AsTagRenderable + TagRenderable {}
+    pub trait GenericRenderable: AsTagRenderable + TagRenderable + Send + Sync {}
 
     impl<T: GenericRenderable> AsTagRenderable for T {
         fn as_tag_renderable(&self) -> &dyn TagRenderable {
@@ -36,13 +120,21 @@ pub mod element_traits {
         fn as_tag_renderable(&self) -> &dyn TagRenderable;
     }
 
+    /// Tags are `Send + Sync` so that element trees can be built on one thread (or task) and
+    /// rendered on another.
     pub trait TagRenderable:
-        TagRenderableName + TagRenderableAttrs + TagRenderableChildren
+        TagRenderableName + TagRenderableAttrs + TagRenderableChildren + Send + Sync
     {
     }
 
     pub trait TagRenderableName {
         fn get_name(&self) -> String;
+
+        /// Void elements (like `<img>`) can never have children and are rendered without a
+        /// closing tag.
+        fn is_void(&self) -> bool {
+            false
+        }
     }
 
     pub trait TagRenderableAttrs {
@@ -50,124 +142,1645 @@ pub mod element_traits {
     }
 
     pub trait TagRenderableStyleSetter<'a> {
-        fn add_style(self, styles: Vec<&'a dyn style_traits::Style>) -> Self;
+        fn add_style(self, styles: Vec<Box<dyn style_traits::Style + 'a>>) -> Self;
+    }
+
+    /// Access to the field holding an element's global attributes, which their setters are
+    /// built on.
+    pub trait TagRenderableGlobalAttrs {
+        type GlobalAttrs;
+
+        fn global_attrs_mut(&mut self) -> &mut Self::GlobalAttrs;
     }
 
     pub trait TagRenderableChildren {
-        fn get_children(&self) -> Result<Vec<Renderable>, String>;
+        fn get_children(&self) -> Result<Vec<Renderable<'_>>, RenderError>;
+    }
+
+    /// The deepest an element tree may be nested before rendering gives up.
+    pub const MAX_RENDER_DEPTH: usize = 512;
+
+    #[derive(Debug)]
+    pub enum RenderError {
+        /// An element was given a child which it can not contain
+        InvalidNesting { path: Vec<String>, message: String },
+        /// An attribute holds a value which can not be rendered
+        InvalidAttributeValue {
+            path: Vec<String>,
+            key: String,
+            message: String,
+        },
+        /// The element tree is larger than the renderer is willing to render
+        LimitExceeded { path: Vec<String>, message: String },
+        /// The underlying writer failed
+        Write,
+    }
+
+    impl RenderError {
+        pub fn invalid_nesting<S: Into<String>>(message: S) -> Self {
+            RenderError::InvalidNesting {
+                path: Vec::new(),
+                message: message.into(),
+            }
+        }
+
+        /// The tag names from the root to the failing element.
+        pub fn path(&self) -> &[String] {
+            match self {
+                RenderError::InvalidNesting { path, .. } => path,
+                RenderError::InvalidAttributeValue { path, .. } => path,
+                RenderError::LimitExceeded { path, .. } => path,
+                RenderError::Write => &[],
+            }
+        }
+
+        fn with_path(mut self, new_path: &[String]) -> Self {
+            match self {
+                RenderError::InvalidNesting { ref mut path, .. }
+                | RenderError::InvalidAttributeValue { ref mut path, .. }
+                | RenderError::LimitExceeded { ref mut path, .. } => {
+                    *path = new_path.to_vec();
+                }
+                RenderError::Write => (),
+            };
+            self
+        }
+    }
+
+    impl fmt::Display for RenderError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let path = self.path().join(" > ");
+            match self {
+                RenderError::InvalidNesting { message, .. } => {
+                    write!(f, "invalid nesting at {}: {}", path, message)
+                }
+                RenderError::InvalidAttributeValue { key, message, .. } => write!(
+                    f,
+                    "invalid value for attribute '{}' at {}: {}",
+                    key, path, message
+                ),
+                RenderError::LimitExceeded { message, .. } => {
+                    write!(f, "limit exceeded at {}: {}", path, message)
+                }
+                RenderError::Write => write!(f, "failed to write rendered output"),
+            }
+        }
+    }
+
+    impl std::error::Error for RenderError {}
+
+    impl From<fmt::Error> for RenderError {
+        fn from(_: fmt::Error) -> Self {
+            RenderError::Write
+        }
     }
 
-    pub trait TagRenderableIntoElement {
-        fn into_element(self) -> Element;
+    pub trait TagRenderableIntoElement<'a> {
+        fn into_element(self) -> Element<'a>;
     }
 
     pub enum Renderable<'a> {
         Tag(&'a dyn TagRenderable),
-        Text(String),
+        Text(Cow<'a, str>),
+        Raw(Cow<'a, str>),
+        Comment(Cow<'a, str>),
+        ConditionalComment {
+            condition: Cow<'a, str>,
+            children: Vec<Renderable<'a>>,
+        },
+        Fragment(Vec<Renderable<'a>>),
+    }
+
+    /// Elements whose contents are whitespace sensitive.
+    const PREFORMATTED_ELEMENTS: [&str; 2] = ["pre", "textarea"];
+
+    /// Controls how `render_pretty` lays out the element tree.
+    pub struct PrettyConfig {
+        /// Written once per level of nesting
+        pub indent: String,
+        /// Elements whose whole rendering fits in this many characters (including their
+        /// indentation) are kept on a single line
+        pub max_width: Option<usize>,
+        /// Elements which flow with the surrounding text, these never start a new line
+        pub inline_elements: HashSet<String>,
+        /// Elements whose contents are whitespace sensitive, these are never reformatted
+        pub preformatted_elements: HashSet<String>,
+    }
+
+    impl Default for PrettyConfig {
+        fn default() -> Self {
+            PrettyConfig {
+                indent: "\t".into(),
+                max_width: None,
+                inline_elements: [
+                    "a", "abbr", "b", "bdi", "bdo", "br", "button", "cite", "code", "data", "dfn",
+                    "em", "i", "img", "input", "kbd", "label", "mark", "q", "s", "samp", "select",
+                    "small", "span", "strong", "sub", "sup", "textarea", "time", "u", "var", "wbr",
+                ]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+                preformatted_elements: PREFORMATTED_ELEMENTS
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect(),
+            }
+        }
+    }
+
+    /// Where rendering has got to, and whether anything that can not be rendered is left out
+    /// rather than returned as an error.
+    struct RenderState {
+        path: Vec<String>,
+        lenient: bool,
+    }
+
+    impl RenderState {
+        fn strict() -> Self {
+            RenderState {
+                path: Vec::new(),
+                lenient: false,
+            }
+        }
+
+        fn lenient() -> Self {
+            RenderState {
+                path: Vec::new(),
+                lenient: true,
+            }
+        }
+
+        fn skip_or(&self, error: RenderError) -> Result<(), RenderError> {
+            match self.lenient {
+                true => Ok(()),
+                false => Err(error),
+            }
+        }
+    }
+
+    #[derive(Clone, Copy)]
+    enum Layout<'c> {
+        Compact,
+        Pretty(&'c PrettyConfig, usize),
+        Minified { omit_end_tag: bool },
+        Xhtml,
     }
 
     impl<'a> Renderable<'a> {
+        /// Never fails: elements nested too deeply, attributes with invalid values and any other
+        /// part of the tree that can not be rendered are left out. Use `try_render` to find out
+        /// what was wrong.
         pub fn render(&self) -> String {
-            self.render_helper(None)
+            let mut rendered = String::new();
+            self.render_to(&mut rendered)
+                .expect("writing to a String never fails");
+            rendered
         }
 
+        /// Leaves out whatever can not be rendered, see `render`.
         pub fn render_pretty(&self) -> String {
-            self.render_helper(Some(0))
+            self.render_pretty_with(&PrettyConfig::default())
+        }
+
+        /// Leaves out whatever can not be rendered, see `render`.
+        pub fn render_pretty_with(&self, config: &PrettyConfig) -> String {
+            let mut rendered = String::new();
+            self.pretty_to(&mut rendered, config, &mut RenderState::lenient())
+                .expect("writing to a String never fails");
+            rendered
+        }
+
+        /// Leaves out whatever can not be rendered, see `render`.
+        pub fn render_minified(&self) -> String {
+            let mut rendered = String::new();
+            self.render_minified_to(&mut rendered)
+                .expect("writing to a String never fails");
+            rendered
+        }
+
+        /// Leaves out whatever can not be rendered, see `render`.
+        pub fn render_xhtml(&self) -> String {
+            let mut rendered = String::new();
+            self.render_xhtml_to(&mut rendered)
+                .expect("writing to a String never fails");
+            rendered
+        }
+
+        /// Leaves out whatever can not be rendered, see `render`.
+        pub fn render_to<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+            self.render_helper(w, Layout::Compact, &mut RenderState::lenient())
+                .map_err(|_| fmt::Error)
+        }
+
+        /// Leaves out whatever can not be rendered, see `render`.
+        pub fn render_pretty_to<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+            self.pretty_to(w, &PrettyConfig::default(), &mut RenderState::lenient())
+                .map_err(|_| fmt::Error)
+        }
+
+        /// Leaves out whatever can not be rendered, see `render`.
+        pub fn render_minified_to<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+            self.minified_to(w, &mut RenderState::lenient())
+                .map_err(|_| fmt::Error)
+        }
+
+        /// Leaves out whatever can not be rendered, see `render`.
+        pub fn render_xhtml_to<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+            self.render_helper(w, Layout::Xhtml, &mut RenderState::lenient())
+                .map_err(|_| fmt::Error)
+        }
+
+        pub fn try_render(&self) -> Result<String, RenderError> {
+            let mut rendered = String::new();
+            self.try_render_to(&mut rendered)?;
+            Ok(rendered)
+        }
+
+        pub fn try_render_pretty(&self) -> Result<String, RenderError> {
+            self.try_render_pretty_with(&PrettyConfig::default())
+        }
+
+        pub fn try_render_pretty_with(&self, config: &PrettyConfig) -> Result<String, RenderError> {
+            let mut rendered = String::new();
+            self.try_render_pretty_with_to(&mut rendered, config)?;
+            Ok(rendered)
+        }
+
+        /// Renders the smallest equivalent html: insignificant whitespace is collapsed, comments
+        /// and optional end tags are dropped, attributes are left unquoted where possible and
+        /// any css is minified.
+        pub fn try_render_minified(&self) -> Result<String, RenderError> {
+            let mut rendered = String::new();
+            self.try_render_minified_to(&mut rendered)?;
+            Ok(rendered)
+        }
+
+        pub fn try_render_minified_to<W: fmt::Write>(&self, w: &mut W) -> Result<(), RenderError> {
+            self.minified_to(w, &mut RenderState::strict())
+        }
+
+        /// Renders well formed xml (polyglot xhtml): void elements are self closed, text is
+        /// escaped by xml's rules, `<html>` declares the xhtml namespace and style sheets are
+        /// wrapped in CDATA sections where needed.
+        pub fn try_render_xhtml(&self) -> Result<String, RenderError> {
+            let mut rendered = String::new();
+            self.try_render_xhtml_to(&mut rendered)?;
+            Ok(rendered)
         }
 
-        fn render_helper(&self, indent: Option<usize>) -> String {
+        pub fn try_render_xhtml_to<W: fmt::Write>(&self, w: &mut W) -> Result<(), RenderError> {
+            self.render_helper(w, Layout::Xhtml, &mut RenderState::strict())
+        }
+
+        pub fn try_render_to<W: fmt::Write>(&self, w: &mut W) -> Result<(), RenderError> {
+            self.render_helper(w, Layout::Compact, &mut RenderState::strict())
+        }
+
+        pub fn try_render_pretty_to<W: fmt::Write>(&self, w: &mut W) -> Result<(), RenderError> {
+            self.try_render_pretty_with_to(w, &PrettyConfig::default())
+        }
+
+        pub fn try_render_pretty_with_to<W: fmt::Write>(
+            &self,
+            w: &mut W,
+            config: &PrettyConfig,
+        ) -> Result<(), RenderError> {
+            self.pretty_to(w, config, &mut RenderState::strict())
+        }
+
+        pub fn render_to_io<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+            let mut writer = IoWriter::new(w);
+            let result = self.try_render_to(&mut writer);
+            writer.into_result(result)
+        }
+
+        pub fn render_pretty_to_io<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+            let mut writer = IoWriter::new(w);
+            let result = self.try_render_pretty_to(&mut writer);
+            writer.into_result(result)
+        }
+
+        pub fn render_minified_to_io<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+            let mut writer = IoWriter::new(w);
+            let result = self.try_render_minified_to(&mut writer);
+            writer.into_result(result)
+        }
+
+        pub fn render_xhtml_to_io<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+            let mut writer = IoWriter::new(w);
+            let result = self.try_render_xhtml_to(&mut writer);
+            writer.into_result(result)
+        }
+
+        fn pretty_to<W: fmt::Write>(
+            &self,
+            w: &mut W,
+            config: &PrettyConfig,
+            state: &mut RenderState,
+        ) -> Result<(), RenderError> {
+            match self {
+                Renderable::Tag(_) => {
+                    write_new_line_and_indent(w, config, 0)?;
+                    self.render_helper(w, Layout::Pretty(config, 0), state)
+                }
+                Renderable::Fragment(children) => {
+                    let children = flatten_fragments(children);
+                    write_children_on_lines(w, config, 0, &children, state)
+                }
+                _ => self.render_helper(w, Layout::Pretty(config, 0), state),
+            }
+        }
+
+        fn minified_to<W: fmt::Write>(
+            &self,
+            w: &mut W,
+            state: &mut RenderState,
+        ) -> Result<(), RenderError> {
+            write_children_minified(
+                w,
+                None,
+                &flatten_fragments(std::slice::from_ref(self)),
+                state,
+            )
+        }
+
+        fn render_helper<W: fmt::Write>(
+            &self,
+            w: &mut W,
+            layout: Layout,
+            state: &mut RenderState,
+        ) -> Result<(), RenderError> {
             match self {
-                Renderable::Tag(ref tag_element) => {
+                Renderable::Tag(tag_element) => {
                     let name = tag_element.get_name();
-                    let attrs = tag_element.get_attributes();
-                    let children = tag_element.get_children();
-                    let rendered_children = match children {
-                        Ok(ref v) => v.iter().fold("".into(), |prev, curr| {
-                            format!("{}{}", prev, curr.render_helper(indent.map(|x| x + 1)))
-                        }),
-                        Err(ref s) => s.clone(),
+                    state.path.push(name.clone());
+                    if state.path.len() > MAX_RENDER_DEPTH {
+                        let error = RenderError::LimitExceeded {
+                            path: state.path.clone(),
+                            message: format!(
+                                "elements are nested deeper than {}",
+                                MAX_RENDER_DEPTH
+                            ),
+                        };
+                        state.path.pop();
+                        return state.skip_or(error);
+                    }
+
+                    let mut attrs = tag_element.get_attributes();
+                    for attr in attrs.iter() {
+                        if let Err(message) = attr.validate_attr_value() {
+                            if !state.lenient {
+                                return Err(RenderError::InvalidAttributeValue {
+                                    path: state.path.clone(),
+                                    key: attr.attr_key(),
+                                    message,
+                                });
+                            }
+                        }
+                    }
+                    if state.lenient {
+                        attrs.retain(|attr| attr.validate_attr_value().is_ok());
+                    }
+
+                    w.write_char('<')?;
+                    w.write_str(&name)?;
+                    match layout {
+                        Layout::Minified { .. } => {
+                            attr_traits::write_attributes_minified(w, attrs)?
+                        }
+                        Layout::Xhtml => {
+                            attr_traits::write_attributes_xml(w, attrs.as_slice())?;
+                            // The root of an xhtml document must declare its namespace
+                            if name == "html" && !attrs.iter().any(|a| a.attr_key() == "xmlns") {
+                                w.write_str(" xmlns=\"http://www.w3.org/1999/xhtml\"")?;
+                            }
+                        }
+                        _ => attr_traits::write_attributes(w, attrs)?,
+                    };
+                    match (layout, tag_element.is_void()) {
+                        (Layout::Xhtml, true) => w.write_str("/>")?,
+                        _ => w.write_char('>')?,
                     };
 
-                    let (
-                        leading_new_line,
-                        leading_indent_string,
-                        closing_new_line,
-                        closing_indent_string,
-                    ) = match indent {
-                        Some(v) => {
-                            let all_children_text = match children {
-                                Ok(v) => v.iter().fold(true, |prev, curr| {
-                                    prev && match curr {
-                                        Renderable::Tag(_) => false,
-                                        Renderable::Text(_) => true,
-                                    }
-                                }),
-                                Err(_) => true,
+                    if !tag_element.is_void() {
+                        let children = match tag_element.get_children() {
+                            Ok(children) => children,
+                            Err(_) if state.lenient => Vec::new(),
+                            Err(e) => return Err(e.with_path(&state.path)),
+                        };
+
+                        let children = flatten_fragments(&children);
+
+                        match layout {
+                            Layout::Pretty(config, level)
+                                if !config.preformatted_elements.contains(&name)
+                                    && children.iter().any(|c| is_block(config, c))
+                                    && !fits_on_line(config, level, self) =>
+                            {
+                                write_children_on_lines(w, config, level + 1, &children, state)?;
+                                write_new_line_and_indent(w, config, level)?;
+                            }
+                            Layout::Minified { .. } => {
+                                write_children_minified(w, Some(&name), &children, state)?;
+                            }
+                            Layout::Xhtml => {
+                                for child in children.iter() {
+                                    child.render_helper(w, Layout::Xhtml, state)?;
+                                }
+                            }
+                            _ => {
+                                for child in children.iter() {
+                                    child.render_helper(w, Layout::Compact, state)?;
+                                }
+                            }
+                        }
+
+                        if !matches!(layout, Layout::Minified { omit_end_tag: true }) {
+                            w.write_str("</")?;
+                            w.write_str(&name)?;
+                            w.write_char('>')?;
+                        }
+                    }
+
+                    state.path.pop();
+                    Ok(())
+                }
+                Renderable::Text(t) => match layout {
+                    Layout::Minified { .. } if !is_preformatted(&state.path) => {
+                        Ok(escape::write_escaped_text(w, &collapse_whitespace(t))?)
+                    }
+                    Layout::Xhtml => Ok(escape::write_escaped_xml_text(w, t)?),
+                    _ => Ok(escape::write_escaped_text(w, t)?),
+                },
+                Renderable::Raw(r) => match layout {
+                    Layout::Minified { .. }
+                        if state.path.last().map(|n| n.as_str()) == Some("style") =>
+                    {
+                        Ok(w.write_str(&style_traits::minify_css(r))?)
+                    }
+                    Layout::Xhtml if state.path.last().map(|n| n.as_str()) == Some("style") => {
+                        Ok(escape::write_css_as_xml(w, r)?)
+                    }
+                    _ => Ok(w.write_str(r)?),
+                },
+                Renderable::Comment(_)
+                    if matches!(layout, Layout::Minified { .. })
+                        || in_conditional_comment(&state.path) =>
+                {
+                    Ok(())
+                }
+                Renderable::Comment(c) => {
+                    w.write_str("<!--")?;
+                    escape::write_escaped_comment(w, c)?;
+                    Ok(w.write_str("-->")?)
+                }
+                Renderable::ConditionalComment {
+                    condition,
+                    children,
+                } => {
+                    if in_conditional_comment(&state.path) {
+                        return state.skip_or(RenderError::InvalidNesting {
+                            path: state.path.clone(),
+                            message: "conditional comments can not be nested".into(),
+                        });
+                    }
+                    state.path.push(format!("[if {}]", condition));
+                    w.write_str("<!--[if ")?;
+                    w.write_str(condition)?;
+                    w.write_str("]>")?;
+                    match layout {
+                        Layout::Minified { .. } => {
+                            write_children_minified(w, None, &flatten_fragments(children), state)?
+                        }
+                        _ => {
+                            let layout = match layout {
+                                Layout::Pretty(..) => Layout::Compact,
+                                _ => layout,
                             };
+                            for child in children.iter() {
+                                child.render_helper(w, layout, state)?;
+                            }
+                        }
+                    }
+                    w.write_str("<![endif]-->")?;
+                    state.path.pop();
+                    Ok(())
+                }
+                Renderable::Fragment(children) => match layout {
+                    Layout::Minified { .. } => {
+                        write_children_minified(w, None, &flatten_fragments(children), state)
+                    }
+                    _ => {
+                        for child in children.iter() {
+                            child.render_helper(w, layout, state)?;
+                        }
+                        Ok(())
+                    }
+                },
+            }
+        }
+    }
 
-                            let (closing_new_line, closing_indent_string): (String, String) =
-                                match all_children_text {
-                                    true => ("".into(), "".into()),
-                                    false => ("\n".into(), "\t".repeat(v)),
-                                };
-                            (
-                                "\n",
-                                "\t".repeat(v),
-                                closing_new_line,
-                                closing_indent_string,
-                            )
+    /// Renders each child minified, working out for each whether its end tag can be dropped.
+    fn write_children_minified<W: fmt::Write>(
+        w: &mut W,
+        parent: Option<&str>,
+        children: &[&Renderable],
+        state: &mut RenderState,
+    ) -> Result<(), RenderError> {
+        let children: Vec<&Renderable> = children
+            .iter()
+            .filter(|c| !matches!(c, Renderable::Comment(_)))
+            .copied()
+            .collect();
+        for (i, child) in children.iter().enumerate() {
+            let omit_end_tag = match child {
+                Renderable::Tag(t) => {
+                    can_omit_end_tag(&t.get_name(), children.get(i + 1).copied(), parent)
+                }
+                _ => false,
+            };
+            child.render_helper(w, Layout::Minified { omit_end_tag }, state)?;
+        }
+        Ok(())
+    }
+
+    /// Whether the end tag of `name` is optional, given what follows it and its parent, per the
+    /// html spec's rules on optional tags.
+    fn can_omit_end_tag(name: &str, next: Option<&Renderable>, parent: Option<&str>) -> bool {
+        let next_name = match next {
+            Some(Renderable::Tag(t)) => Some(t.get_name()),
+            _ => None,
+        };
+        let followed_by = |names: &[&str]| match next_name {
+            Some(ref n) => names.contains(&n.as_str()),
+            None => false,
+        };
+        // Whether this is the last child of a parent element; at the root what comes next is
+        // not known
+        let last = next.is_none() && parent.is_some();
+
+        match name {
+            "html" | "body" => true,
+            "head" => !matches!(next, Some(Renderable::Text(_))),
+            "li" => last || followed_by(&["li"]),
+            "dt" => followed_by(&["dt", "dd"]),
+            "dd" => last || followed_by(&["dt", "dd"]),
+            "td" | "th" => last || followed_by(&["td", "th"]),
+            "tr" => last || followed_by(&["tr"]),
+            "thead" => followed_by(&["tbody", "tfoot"]),
+            "tbody" => last || followed_by(&["tbody", "tfoot"]),
+            "tfoot" => last,
+            "option" => last || followed_by(&["option", "optgroup"]),
+            "optgroup" => last || followed_by(&["optgroup"]),
+            "p" => {
+                followed_by(&[
+                    "address",
+                    "article",
+                    "aside",
+                    "blockquote",
+                    "details",
+                    "dialog",
+                    "div",
+                    "dl",
+                    "fieldset",
+                    "figcaption",
+                    "figure",
+                    "footer",
+                    "form",
+                    "h1",
+                    "h2",
+                    "h3",
+                    "h4",
+                    "h5",
+                    "h6",
+                    "header",
+                    "hgroup",
+                    "hr",
+                    "main",
+                    "menu",
+                    "nav",
+                    "ol",
+                    "p",
+                    "pre",
+                    "search",
+                    "section",
+                    "table",
+                    "ul",
+                ]) || (last
+                    && match parent {
+                        Some(p) => {
+                            !["a", "audio", "del", "ins", "map", "noscript", "video"].contains(&p)
                         }
-                        None => ("".into(), "".into(), "".into(), "".into()),
-                    };
+                        None => false,
+                    })
+            }
+            _ => false,
+        }
+    }
+
+    /// Conditional comments are in the path by their `[if condition]` opening.
+    fn in_conditional_comment(path: &[String]) -> bool {
+        path.iter().any(|name| name.starts_with("[if "))
+    }
+
+    fn is_preformatted(path: &[String]) -> bool {
+        path.iter()
+            .any(|name| PREFORMATTED_ELEMENTS.contains(&name.as_str()))
+    }
+
+    fn collapse_whitespace(s: &str) -> String {
+        let mut collapsed = String::with_capacity(s.len());
+        let mut previous_whitespace = false;
+        for c in s.chars() {
+            if c.is_ascii_whitespace() {
+                if !previous_whitespace {
+                    collapsed.push(' ');
+                }
+                previous_whitespace = true;
+            } else {
+                collapsed.push(c);
+                previous_whitespace = false;
+            }
+        }
+        collapsed
+    }
+
+    /// Splices the children of any (nested) fragments in with their siblings.
+    fn flatten_fragments<'r, 'a>(children: &'r [Renderable<'a>]) -> Vec<&'r Renderable<'a>> {
+        let mut flattened = Vec::with_capacity(children.len());
+        for child in children.iter() {
+            match child {
+                Renderable::Fragment(f) => flattened.extend(flatten_fragments(f)),
+                _ => flattened.push(child),
+            }
+        }
+        flattened
+    }
+
+    /// Places each block child on its own line, at the given level of indentation.
+    fn write_children_on_lines<W: fmt::Write>(
+        w: &mut W,
+        config: &PrettyConfig,
+        level: usize,
+        children: &[&Renderable],
+        state: &mut RenderState,
+    ) -> Result<(), RenderError> {
+        let mut previous_inline = false;
+        for child in children.iter() {
+            let inline = !is_block(config, child);
+            // A run of inline content stays together on one line, as any whitespace added inside
+            // of it would be visible
+            if !(inline && previous_inline) {
+                write_new_line_and_indent(w, config, level)?;
+            }
+            child.render_helper(
+                w,
+                match inline {
+                    true => Layout::Compact,
+                    false => Layout::Pretty(config, level),
+                },
+                state,
+            )?;
+            previous_inline = inline;
+        }
+        Ok(())
+    }
+
+    /// Whether whitespace can be placed around this child without changing how the page is
+    /// displayed.
+    fn is_block(config: &PrettyConfig, child: &Renderable) -> bool {
+        match child {
+            Renderable::Tag(t) => !config.inline_elements.contains(&t.get_name()),
+            Renderable::Text(_)
+            | Renderable::Raw(_)
+            | Renderable::Comment(_)
+            | Renderable::ConditionalComment { .. }
+            | Renderable::Fragment(_) => false,
+        }
+    }
+
+    fn fits_on_line(config: &PrettyConfig, level: usize, renderable: &Renderable) -> bool {
+        match config.max_width {
+            Some(max_width) => {
+                let indent_width = config.indent.chars().count() * level;
+                if indent_width > max_width {
+                    return false;
+                }
+                let mut counter = WidthCounter {
+                    remaining: max_width - indent_width,
+                };
+                renderable
+                    .render_helper(&mut counter, Layout::Compact, &mut RenderState::strict())
+                    .is_ok()
+            }
+            None => false,
+        }
+    }
+
+    /// Fails as soon as more than `remaining` characters, or any new line, is written.
+    struct WidthCounter {
+        remaining: usize,
+    }
+
+    impl fmt::Write for WidthCounter {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let width = s.chars().count();
+            if width > self.remaining || s.contains('\n') {
+                return Err(fmt::Error);
+            }
+            self.remaining -= width;
+            Ok(())
+        }
+    }
+
+    fn write_new_line_and_indent<W: fmt::Write>(
+        w: &mut W,
+        config: &PrettyConfig,
+        level: usize,
+    ) -> fmt::Result {
+        w.write_char('\n')?;
+        for _ in 0..level {
+            w.write_str(&config.indent)?;
+        }
+        Ok(())
+    }
+
+    /// Adapts an `io::Write` to be written to as an `fmt::Write`, holding on to the underlying
+    /// io error (which `fmt::Error` can not carry).
+    struct IoWriter<'w, W: io::Write> {
+        inner: &'w mut W,
+        error: Option<io::Error>,
+    }
+
+    impl<'w, W: io::Write> IoWriter<'w, W> {
+        fn new(inner: &'w mut W) -> Self {
+            IoWriter { inner, error: None }
+        }
 
-                    format!(
-                        "{}{}<{}{}>{}{}{}</{}>",
-                        leading_new_line,
-                        leading_indent_string,
-                        name,
-                        attr_traits::render_attributes(attrs),
-                        rendered_children,
-                        closing_new_line,
-                        closing_indent_string,
-                        name
-                    )
+        fn into_result(self, result: Result<(), RenderError>) -> io::Result<()> {
+            match (result, self.error) {
+                (Ok(()), _) => Ok(()),
+                (Err(RenderError::Write), Some(e)) => Err(e),
+                (Err(e), _) => Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+            }
+        }
+    }
+
+    impl<'w, W: io::Write> fmt::Write for IoWriter<'w, W> {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            self.inner.write_all(s.as_bytes()).map_err(|e| {
+                self.error = Some(e);
+                fmt::Error
+            })
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn conditional_comment_wraps_its_children() {
+            let element =
+                conditional_comment("lt IE 9", vec![text("old <browser>"), comment("dropped")])
+                    .unwrap();
+            let renderable = element.into_renderable();
+            assert_eq!(
+                renderable.try_render().unwrap(),
+                "<!--[if lt IE 9]>old &lt;browser&gt;<![endif]-->"
+            );
+            assert_eq!(
+                renderable.try_render_minified().unwrap(),
+                "<!--[if lt IE 9]>old &lt;browser&gt;<![endif]-->"
+            );
+        }
+
+        #[test]
+        fn conditional_comment_condition_is_checked() {
+            assert!(conditional_comment("(gte IE 6)&!(IE 8)", vec![]).is_ok());
+            assert!(conditional_comment("IE 5.5", vec![]).is_ok());
+            assert!(conditional_comment("", vec![]).is_err());
+            assert!(conditional_comment("IE]><script>", vec![]).is_err());
+            assert!(conditional_comment("IE -->", vec![]).is_err());
+        }
+
+        struct Named(&'static str);
+
+        impl TagRenderableName for Named {
+            fn get_name(&self) -> String {
+                self.0.to_string()
+            }
+        }
+
+        impl TagRenderableAttrs for Named {
+            fn get_attributes(&self) -> Vec<&dyn attr_traits::Attribute> {
+                Vec::new()
+            }
+        }
+
+        impl TagRenderableChildren for Named {
+            fn get_children(&self) -> Result<Vec<Renderable<'_>>, RenderError> {
+                Ok(Vec::new())
+            }
+        }
+
+        impl TagRenderable for Named {}
+
+        #[test]
+        fn optional_end_tags() {
+            // (element, next sibling, parent, end tag can be omitted), a next sibling of
+            // `#text` is a text node
+            let cases = [
+                ("p", Some("p"), Some("body"), true),
+                ("p", Some("div"), Some("body"), true),
+                ("p", Some("ul"), Some("div"), true),
+                ("p", Some("span"), Some("body"), false),
+                ("p", Some("#text"), Some("body"), false),
+                ("p", None, Some("div"), true),
+                ("p", None, Some("a"), false),
+                ("p", None, Some("video"), false),
+                ("p", None, None, false),
+                ("li", Some("li"), Some("ul"), true),
+                ("li", None, Some("ol"), true),
+                ("li", Some("p"), Some("ul"), false),
+                ("dt", Some("dd"), Some("dl"), true),
+                ("dt", Some("dt"), Some("dl"), true),
+                ("dt", None, Some("dl"), false),
+                ("dd", Some("dt"), Some("dl"), true),
+                ("dd", None, Some("dl"), true),
+                ("thead", Some("tbody"), Some("table"), true),
+                ("thead", None, Some("table"), false),
+                ("tbody", Some("tbody"), Some("table"), true),
+                ("tbody", Some("tfoot"), Some("table"), true),
+                ("tbody", None, Some("table"), true),
+                ("tfoot", None, Some("table"), true),
+                ("tfoot", Some("tbody"), Some("table"), false),
+                ("tr", Some("tr"), Some("tbody"), true),
+                ("td", Some("th"), Some("tr"), true),
+                ("td", None, Some("tr"), true),
+                ("option", Some("option"), Some("select"), true),
+                ("option", Some("optgroup"), Some("select"), true),
+                ("option", None, Some("select"), true),
+                ("option", Some("#text"), Some("select"), false),
+                ("optgroup", Some("optgroup"), Some("select"), true),
+                ("optgroup", Some("option"), Some("select"), false),
+                ("head", Some("body"), Some("html"), true),
+                ("head", Some("#text"), Some("html"), false),
+                ("div", None, Some("body"), false),
+                ("li", None, None, false),
+                ("dd", None, None, false),
+                ("td", None, None, false),
+                ("th", None, None, false),
+                ("tr", None, None, false),
+                ("tbody", None, None, false),
+                ("tfoot", None, None, false),
+                ("option", None, None, false),
+                ("optgroup", None, None, false),
+            ];
+            for (name, next, parent, expected) in cases.iter() {
+                let next_tag = next.map(Named);
+                let next = match (next, next_tag.as_ref()) {
+                    (Some("#text"), _) => Some(Renderable::Text(" ".into())),
+                    (_, Some(tag)) => Some(Renderable::Tag(tag)),
+                    _ => None,
+                };
+                assert_eq!(
+                    can_omit_end_tag(name, next.as_ref(), *parent),
+                    *expected,
+                    "{} followed by {:?} in {:?}",
+                    name,
+                    next_tag.map(|t| t.0),
+                    parent
+                );
+            }
+        }
+
+        #[test]
+        fn root_keeps_its_end_tag() {
+            let td = Named("td");
+            assert_eq!(
+                Renderable::Tag(&td).try_render_minified().unwrap(),
+                "<td></td>"
+            );
+        }
+
+        #[test]
+        fn conditional_comments_can_not_be_nested() {
+            let inner = conditional_comment("IE", vec![text("x")]).unwrap();
+            let outer = conditional_comment("IE", vec![inner]).unwrap();
+            let renderable = outer.into_renderable();
+            assert!(matches!(
+                renderable.try_render(),
+                Err(RenderError::InvalidNesting { .. })
+            ));
+            assert_eq!(renderable.render(), "<!--[if IE]><![endif]-->");
+        }
+
+        struct Checked(&'static str);
+
+        impl attr_traits::Attribute for Checked {
+            fn attr_key(&self) -> String {
+                self.0.to_string()
+            }
+
+            fn attr_value(&self) -> String {
+                "x".into()
+            }
+
+            fn validate_attr_value(&self) -> Result<(), String> {
+                match self.0 {
+                    "bad" => Err("always invalid".into()),
+                    _ => Ok(()),
                 }
-                Renderable::Text(t) => t.clone(),
             }
         }
+
+        struct WithAttrs(Checked, Checked);
+
+        impl TagRenderableName for WithAttrs {
+            fn get_name(&self) -> String {
+                "div".into()
+            }
+        }
+
+        impl TagRenderableAttrs for WithAttrs {
+            fn get_attributes(&self) -> Vec<&dyn attr_traits::Attribute> {
+                vec![&self.0, &self.1]
+            }
+        }
+
+        impl TagRenderableChildren for WithAttrs {
+            fn get_children(&self) -> Result<Vec<Renderable<'_>>, RenderError> {
+                Ok(vec![Renderable::Text("y".into())])
+            }
+        }
+
+        impl TagRenderable for WithAttrs {}
+
+        #[test]
+        fn render_leaves_out_invalid_attributes() {
+            let tag = WithAttrs(Checked("bad"), Checked("good"));
+            let renderable = Renderable::Tag(&tag);
+            assert!(matches!(
+                renderable.try_render(),
+                Err(RenderError::InvalidAttributeValue { .. })
+            ));
+            assert_eq!(renderable.render(), "<div good=\"x\">y</div>");
+            assert_eq!(renderable.render_pretty(), "\n<div good=\"x\">y</div>");
+            assert_eq!(renderable.render_minified(), "<div good=x>y</div>");
+            assert_eq!(renderable.render_xhtml(), "<div good=\"x\">y</div>");
+        }
     }
 }
 
 pub mod attr_traits {
+    use std::fmt;
+
+    use super::escape;
+    use super::style_traits;
+
     pub trait Attribute {
         fn attr_key(&self) -> String;
         fn attr_value(&self) -> String;
+
+        /// Checked before the attribute is rendered, an `Err` fails the render with a
+        /// `RenderError::InvalidAttributeValue`.
+        fn validate_attr_value(&self) -> Result<(), String> {
+            Ok(())
+        }
+
+        /// Boolean attributes are true by being present, xml has no such shorthand so they are
+        /// written as `key="key"` there.
+        fn is_boolean(&self) -> bool {
+            false
+        }
+    }
+
+    /// A group of attributes which are rendered together, like the global attributes.
+    pub trait AttributeSet {
+        fn attributes(&self) -> Vec<&dyn Attribute>;
     }
 
     pub fn render_attributes(attributes: Vec<&dyn Attribute>) -> String {
-        attributes.into_iter().fold("".into(), |rendered, a| {
-            format!("{} {}", rendered, render_attribute(a))
-        })
+        let mut rendered = String::new();
+        write_attributes(&mut rendered, attributes).expect("writing to a String never fails");
+        rendered
+    }
+
+    pub fn write_attributes<W: fmt::Write>(
+        w: &mut W,
+        attributes: Vec<&dyn Attribute>,
+    ) -> fmt::Result {
+        for attribute in attributes.into_iter() {
+            w.write_char(' ')?;
+            write_attribute(w, attribute)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the attributes escaped by xml's rules, which also keeps whitespace in the values
+    /// from being normalized away by an xml parser.
+    pub fn write_attributes_xml<W: fmt::Write>(
+        w: &mut W,
+        attributes: &[&dyn Attribute],
+    ) -> fmt::Result {
+        for attribute in attributes.iter() {
+            w.write_char(' ')?;
+            let key = attribute.attr_key();
+            let value = match attribute.is_boolean() {
+                true => key.clone(),
+                false => attribute.attr_value(),
+            };
+            w.write_str(&key)?;
+            w.write_str("=\"")?;
+            escape::write_escaped_xml_attr_value(w, &value)?;
+            w.write_char('"')?;
+        }
+        Ok(())
     }
 
-    fn render_attribute(attribute: &dyn Attribute) -> String {
-        format!("{}=\"{}\"", attribute.attr_key(), attribute.attr_value())
+    /// Writes the attributes in their shortest form: empty values are left off entirely, values
+    /// are only quoted where needed and style values are minified.
+    pub fn write_attributes_minified<W: fmt::Write>(
+        w: &mut W,
+        attributes: Vec<&dyn Attribute>,
+    ) -> fmt::Result {
+        for attribute in attributes.into_iter() {
+            let key = attribute.attr_key();
+            let value = match key.as_str() {
+                "style" => style_traits::minify_css(&attribute.attr_value()),
+                _ => attribute.attr_value(),
+            };
+
+            w.write_char(' ')?;
+            w.write_str(&key)?;
+            if value.is_empty() {
+                continue;
+            }
+
+            let needs_quotes = value.ends_with('/')
+                || value.chars().any(|c| {
+                    c.is_ascii_whitespace() || ['"', '\'', '=', '<', '>', '`'].contains(&c)
+                });
+            match needs_quotes {
+                true => {
+                    w.write_str("=\"")?;
+                    escape::write_escaped_attr_value(w, &value)?;
+                    w.write_char('"')?;
+                }
+                false => {
+                    w.write_char('=')?;
+                    escape::write_escaped_attr_value(w, &value)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn write_attribute<W: fmt::Write>(w: &mut W, attribute: &dyn Attribute) -> fmt::Result {
+        w.write_str(&attribute.attr_key())?;
+        w.write_str("=\"")?;
+        escape::write_escaped_attr_value(w, &attribute.attr_value())?;
+        w.write_char('"')
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        struct Attr(&'static str, &'static str);
+
+        impl Attribute for Attr {
+            fn attr_key(&self) -> String {
+                self.0.to_string()
+            }
+
+            fn attr_value(&self) -> String {
+                self.1.to_string()
+            }
+        }
+
+        #[test]
+        fn minified_attributes_are_only_quoted_where_needed() {
+            let cases = [
+                (Attr("id", "main"), " id=main"),
+                (Attr("hidden", ""), " hidden"),
+                (Attr("class", "a b"), " class=\"a b\""),
+                (Attr("title", "tab\there"), " title=\"tab\there\""),
+                (Attr("href", "/a/"), " href=\"/a/\""),
+                (Attr("href", "/a/b"), " href=/a/b"),
+                (Attr("href", "?a=b"), " href=\"?a=b\""),
+                (Attr("title", "it's"), " title=\"it&#39;s\""),
+                (Attr("title", "say \"hi\""), " title=\"say &quot;hi&quot;\""),
+                (Attr("title", "a<b"), " title=\"a&lt;b\""),
+                (Attr("title", "`x`"), " title=\"`x`\""),
+                (Attr("title", "a&b"), " title=a&amp;b"),
+                (
+                    Attr("style", "color: red; margin: 0 auto"),
+                    " style=\"color:red;margin:0 auto\"",
+                ),
+                (Attr("style", "color: red;"), " style=color:red"),
+            ];
+            for (attr, expected) in cases.iter() {
+                let mut rendered = String::new();
+                write_attributes_minified(&mut rendered, vec![attr as &dyn Attribute]).unwrap();
+                assert_eq!(rendered, *expected, "{}={}", attr.0, attr.1);
+            }
+        }
     }
 }
 
 pub mod style_traits {
-    pub trait Style {
+    /// Styles are held by elements, so they must be `Send + Sync` for the elements to be.
+    pub trait Style: Send + Sync {
         fn style_key(&self) -> String;
         fn style_value(&self) -> String;
+
+        /// Rendered with `!important`, which wins over any later declaration that is not.
+        fn is_important(&self) -> bool {
+            false
+        }
+
+        /// Kept even when a later declaration sets the same property, so that it can act as a
+        /// fallback for browsers which do not understand the later one.
+        fn is_fallback(&self) -> bool {
+            false
+        }
     }
 
-    pub fn render_styles(styles: &Vec<&dyn Style>) -> String {
-        styles.into_iter().fold("".into(), |rendered, s| {
-            format!("{}; {}", render_style(*s), rendered)
-        })
+    /// Renders the declarations in the order they were given. When more than one sets the same
+    /// property only the one which takes effect is kept: the last `!important` one, or else the
+    /// last one. Fallbacks are always kept.
+    pub fn render_styles(styles: &[Box<dyn Style + '_>]) -> String {
+        let keys: Vec<String> = styles.iter().map(|s| s.style_key()).collect();
+        let takes_effect = |i: usize| {
+            let competing = || {
+                styles
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, other)| keys[*j] == keys[i] && !other.is_fallback())
+            };
+            let winner = match competing().rfind(|(_, s)| s.is_important()) {
+                Some((j, _)) => j,
+                None => competing().next_back().map_or(i, |(j, _)| j),
+            };
+            winner == i
+        };
+
+        styles
+            .iter()
+            .enumerate()
+            .filter(|(i, s)| s.is_fallback() || takes_effect(*i))
+            .map(|(_, s)| render_style(&**s))
+            .collect::<Vec<String>>()
+            .join("; ")
     }
 
     fn render_style(style: &dyn Style) -> String {
-        format!("{}: {}", style.style_key(), style.style_value())
+        match style.is_important() {
+            true => format!("{}: {} !important", style.style_key(), style.style_value()),
+            false => format!("{}: {}", style.style_key(), style.style_value()),
+        }
+    }
+
+    /// Strips comments and any whitespace which css does not need, from either a list of
+    /// declarations or a full style sheet.
+    pub fn minify_css(css: &str) -> String {
+        let mut minified = String::with_capacity(css.len());
+        let mut chars = css.chars().peekable();
+        let mut pending_space = false;
+        while let Some(c) = chars.next() {
+            match c {
+                '"' | '\'' => {
+                    if pending_space && needs_space_after(minified.chars().last()) {
+                        minified.push(' ');
+                    }
+                    pending_space = false;
+                    minified.push(c);
+                    while let Some(s) = chars.next() {
+                        minified.push(s);
+                        if s == '\\' {
+                            if let Some(escaped) = chars.next() {
+                                minified.push(escaped);
+                            }
+                        } else if s == c {
+                            break;
+                        }
+                    }
+                }
+                '/' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    let mut previous = None;
+                    for s in chars.by_ref() {
+                        if previous == Some('*') && s == '/' {
+                            break;
+                        }
+                        previous = Some(s);
+                    }
+                    pending_space = true;
+                }
+                c if c.is_whitespace() => pending_space = true,
+                '{' | '}' | ';' | ',' | '>' | ':' => {
+                    if c == '}' && minified.ends_with(';') {
+                        minified.pop();
+                    }
+                    // Whitespace before a colon is kept, as in selectors it separates a
+                    // descendant from a pseudo class
+                    if c == ':' && pending_space && needs_space_after(minified.chars().last()) {
+                        minified.push(' ');
+                    }
+                    pending_space = false;
+                    minified.push(c);
+                    while chars.peek().is_some_and(|n| n.is_whitespace()) {
+                        chars.next();
+                    }
+                }
+                _ => {
+                    if pending_space && needs_space_after(minified.chars().last()) {
+                        minified.push(' ');
+                    }
+                    pending_space = false;
+                    minified.push(c);
+                }
+            }
+        }
+        if minified.ends_with(';') {
+            minified.pop();
+        }
+        minified
+    }
+
+    fn needs_space_after(previous: Option<char>) -> bool {
+        match previous {
+            Some(p) => !['{', '}', ';', ',', '>', ':'].contains(&p),
+            None => false,
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        struct Decl {
+            key: &'static str,
+            value: &'static str,
+            important: bool,
+            fallback: bool,
+        }
+
+        impl Style for Decl {
+            fn style_key(&self) -> String {
+                self.key.to_string()
+            }
+
+            fn style_value(&self) -> String {
+                self.value.to_string()
+            }
+
+            fn is_important(&self) -> bool {
+                self.important
+            }
+
+            fn is_fallback(&self) -> bool {
+                self.fallback
+            }
+        }
+
+        fn normal(key: &'static str, value: &'static str) -> Box<dyn Style> {
+            Box::new(Decl {
+                key,
+                value,
+                important: false,
+                fallback: false,
+            })
+        }
+
+        fn important(key: &'static str, value: &'static str) -> Box<dyn Style> {
+            Box::new(Decl {
+                key,
+                value,
+                important: true,
+                fallback: false,
+            })
+        }
+
+        fn fallback(key: &'static str, value: &'static str) -> Box<dyn Style> {
+            Box::new(Decl {
+                key,
+                value,
+                important: false,
+                fallback: true,
+            })
+        }
+
+        #[test]
+        fn render_styles_keeps_the_declaration_which_takes_effect() {
+            let cases = vec![
+                (vec![], ""),
+                (
+                    vec![normal("color", "red"), normal("margin", "0")],
+                    "color: red; margin: 0",
+                ),
+                (
+                    vec![
+                        normal("color", "red"),
+                        normal("margin", "0"),
+                        normal("color", "blue"),
+                    ],
+                    "margin: 0; color: blue",
+                ),
+                (
+                    vec![important("color", "red"), normal("color", "blue")],
+                    "color: red !important",
+                ),
+                (
+                    vec![
+                        important("color", "red"),
+                        normal("color", "blue"),
+                        important("color", "green"),
+                    ],
+                    "color: green !important",
+                ),
+                (
+                    vec![normal("color", "blue"), important("color", "red")],
+                    "color: red !important",
+                ),
+                (
+                    vec![
+                        fallback("width", "100px"),
+                        normal("width", "calc(100% - 1rem)"),
+                    ],
+                    "width: 100px; width: calc(100% - 1rem)",
+                ),
+                (
+                    vec![
+                        fallback("display", "block"),
+                        normal("display", "flex"),
+                        normal("display", "grid"),
+                    ],
+                    "display: block; display: grid",
+                ),
+                (
+                    vec![fallback("color", "red"), fallback("color", "blue")],
+                    "color: red; color: blue",
+                ),
+            ];
+            for (styles, expected) in cases.iter() {
+                assert_eq!(render_styles(styles), *expected);
+            }
+        }
+
+        #[test]
+        fn minify_css_keeps_significant_whitespace() {
+            let cases = [
+                ("color: red; ", "color:red"),
+                ("width: calc(100% - 2rem)", "width:calc(100% - 2rem)"),
+                ("width: calc( 1px  +  2px )", "width:calc( 1px + 2px )"),
+                ("margin: 0   auto", "margin:0 auto"),
+                (
+                    "font-family: \"a  b\" , serif",
+                    "font-family:\"a  b\",serif",
+                ),
+                ("content: 'x ; y'", "content:'x ; y'"),
+                ("a /* note */ { color: red ; }", "a{color:red}"),
+                ("ul > li , p a:hover { }", "ul>li,p a:hover{}"),
+                ("p :first-child {}", "p :first-child{}"),
+                ("  \n", ""),
+            ];
+            for (css, expected) in cases.iter() {
+                assert_eq!(minify_css(css), *expected, "{}", css);
+            }
+        }
+    }
+}
+
+pub mod escape {
+    use std::fmt;
+
+    /// Escapes text content so that it can not open or close any markup.
+    pub fn escape_text(s: &str) -> String {
+        let mut escaped = String::with_capacity(s.len());
+        write_escaped_text(&mut escaped, s).expect("writing to a String never fails");
+        escaped
+    }
+
+    pub fn write_escaped_text<W: fmt::Write>(w: &mut W, s: &str) -> fmt::Result {
+        write_escaped(w, s, |c| match c {
+            '&' => Some("&amp;"),
+            '<' => Some("&lt;"),
+            '>' => Some("&gt;"),
+            _ => None,
+        })
+    }
+
+    /// Escapes an attribute value so that it can not terminate its surrounding quotes.
+    pub fn escape_attr_value(s: &str) -> String {
+        let mut escaped = String::with_capacity(s.len());
+        write_escaped_attr_value(&mut escaped, s).expect("writing to a String never fails");
+        escaped
+    }
+
+    pub fn write_escaped_attr_value<W: fmt::Write>(w: &mut W, s: &str) -> fmt::Result {
+        write_escaped(w, s, |c| match c {
+            '&' => Some("&amp;"),
+            '<' => Some("&lt;"),
+            '>' => Some("&gt;"),
+            '"' => Some("&quot;"),
+            '\'' => Some("&#39;"),
+            _ => None,
+        })
+    }
+
+    /// Escapes the contents of a raw text element (like `<style>`), whose contents are not
+    /// entity decoded by the browser, so that they can not close the element early.
+    pub fn escape_raw_text(element_name: &str, s: &str) -> String {
+        let mut escaped = String::with_capacity(s.len());
+        write_escaped_raw_text(&mut escaped, element_name, s)
+            .expect("writing to a String never fails");
+        escaped
+    }
+
+    pub fn write_escaped_raw_text<W: fmt::Write>(
+        w: &mut W,
+        element_name: &str,
+        s: &str,
+    ) -> fmt::Result {
+        let closing = format!("</{}", element_name.to_lowercase());
+        let mut rest = s;
+        while let Some(i) = find_ignore_ascii_case(rest, &closing) {
+            w.write_str(&rest[..i])?;
+            w.write_str("<\\/")?;
+            rest = &rest[i + 2..];
+        }
+        w.write_str(rest)
+    }
+
+    /// Escapes text content by xml's rules, dropping any characters which xml can not represent.
+    pub fn write_escaped_xml_text<W: fmt::Write>(w: &mut W, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            match c {
+                '&' => w.write_str("&amp;")?,
+                '<' => w.write_str("&lt;")?,
+                '>' => w.write_str("&gt;")?,
+                c if !is_xml_char(c) => (),
+                c => w.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Escapes an attribute value by xml's rules, including the whitespace which an xml parser
+    /// would otherwise normalize to spaces.
+    pub fn write_escaped_xml_attr_value<W: fmt::Write>(w: &mut W, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            match c {
+                '&' => w.write_str("&amp;")?,
+                '<' => w.write_str("&lt;")?,
+                '>' => w.write_str("&gt;")?,
+                '"' => w.write_str("&quot;")?,
+                '\'' => w.write_str("&apos;")?,
+                '\t' => w.write_str("&#9;")?,
+                '\n' => w.write_str("&#10;")?,
+                '\r' => w.write_str("&#13;")?,
+                c if !is_xml_char(c) => (),
+                c => w.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes a style sheet so that an xml parser reads it unchanged, wrapping it in a CDATA
+    /// section (hidden from css in comments) when it holds any markup characters.
+    pub fn write_css_as_xml<W: fmt::Write>(w: &mut W, css: &str) -> fmt::Result {
+        if !css.contains(['<', '&']) {
+            return w.write_str(css);
+        }
+        w.write_str("/*<![CDATA[*/")?;
+        let mut rest = css;
+        while let Some(i) = rest.find("]]>") {
+            w.write_str(&rest[..i])?;
+            w.write_str("]]]]><![CDATA[>")?;
+            rest = &rest[i + 3..];
+        }
+        w.write_str(rest)?;
+        w.write_str("/*]]>*/")
+    }
+
+    fn is_xml_char(c: char) -> bool {
+        matches!(c, '\t' | '\n' | '\r' | '\u{20}'..='\u{d7ff}' | '\u{e000}'..='\u{fffd}' | '\u{10000}'..)
+    }
+
+    /// Escapes comment text so that it can not end the comment early, by breaking up any `--`
+    /// and keeping it from starting with `>` or `->` or ending with `-`.
+    pub fn escape_comment(s: &str) -> String {
+        let mut escaped = String::with_capacity(s.len());
+        write_escaped_comment(&mut escaped, s).expect("writing to a String never fails");
+        escaped
+    }
+
+    pub fn write_escaped_comment<W: fmt::Write>(w: &mut W, s: &str) -> fmt::Result {
+        if s.starts_with('>') || s.starts_with("->") {
+            w.write_char(' ')?;
+        }
+        let mut previous = None;
+        for c in s.chars() {
+            if c == '-' && (previous == Some('-') || previous == Some('!')) {
+                w.write_char(' ')?;
+            }
+            w.write_char(c)?;
+            previous = Some(c);
+        }
+        if previous == Some('-') {
+            w.write_char(' ')?;
+        }
+        Ok(())
+    }
+
+    fn write_escaped<W: fmt::Write, F: Fn(char) -> Option<&'static str>>(
+        w: &mut W,
+        s: &str,
+        replacement: F,
+    ) -> fmt::Result {
+        let mut last = 0;
+        for (i, c) in s.char_indices() {
+            if let Some(r) = replacement(c) {
+                w.write_str(&s[last..i])?;
+                w.write_str(r)?;
+                last = i + c.len_utf8();
+            }
+        }
+        w.write_str(&s[last..])
+    }
+
+    fn find_ignore_ascii_case(haystack: &str, needle: &str) -> Option<usize> {
+        haystack
+            .char_indices()
+            .map(|(i, _)| i)
+            .find(|&i| match haystack.get(i..i + needle.len()) {
+                Some(candidate) => candidate.eq_ignore_ascii_case(needle),
+                None => false,
+            })
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn text_escapes_markup_characters() {
+            assert_eq!(
+                escape_text("a < b && c > d"),
+                "a &lt; b &amp;&amp; c &gt; d"
+            );
+            assert_eq!(escape_text("<script>"), "&lt;script&gt;");
+            assert_eq!(escape_text("\"quotes\" 'stay'"), "\"quotes\" 'stay'");
+            assert_eq!(escape_text("caf\u{e9} \u{1f600}"), "caf\u{e9} \u{1f600}");
+        }
+
+        #[test]
+        fn attr_value_escapes_quotes() {
+            assert_eq!(
+                escape_attr_value("x\" onclick='y'"),
+                "x&quot; onclick=&#39;y&#39;"
+            );
+            assert_eq!(escape_attr_value("a&b<c>"), "a&amp;b&lt;c&gt;");
+        }
+
+        #[test]
+        fn raw_text_can_not_close_its_element() {
+            let cases = [
+                ("a { color: red }", "a { color: red }"),
+                ("</style><script>", "<\\/style><script>"),
+                ("</STYLE>", "<\\/STYLE>"),
+                ("</StYlE >", "<\\/StYlE >"),
+                ("x</style</style", "x<\\/style<\\/style"),
+                ("</styles", "<\\/styles"),
+                ("</script>", "</script>"),
+            ];
+            for (input, expected) in cases.iter() {
+                assert_eq!(escape_raw_text("style", input), *expected, "{}", input);
+            }
+            assert_eq!(escape_raw_text("STYLE", "</style>"), "<\\/style>");
+        }
+
+        #[test]
+        fn comment_can_not_end_early() {
+            let cases = [
+                ("plain", "plain"),
+                ("a -- b", "a - - b"),
+                ("--->", "- - ->"),
+                ("x --!> y", "x - -!> y"),
+                ("<!-- nested", "<! - - nested"),
+                ("<!-", "<! - "),
+                (">x", " >x"),
+                ("->x", " ->x"),
+                ("trailing-", "trailing- "),
+            ];
+            for (input, expected) in cases.iter() {
+                assert_eq!(escape_comment(input), *expected, "{}", input);
+                assert!(!escape_comment(input).contains("--"), "{}", input);
+            }
+        }
     }
 }
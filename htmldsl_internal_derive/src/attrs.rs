@@ -0,0 +1,80 @@
+use proc_macro::TokenStream;
+
+fn get_ast_data(ast: &syn::DeriveInput) -> &syn::DataStruct {
+    match ast.data {
+        syn::Data::Struct(ref s) => s,
+        _ => panic!("derive attrs only works on structs"),
+    }
+}
+
+fn get_named_fields(fields: &syn::Fields) -> &syn::FieldsNamed {
+    match fields {
+        syn::Fields::Named(ref n) => n,
+        _ => panic!("derive attrs only works with named fields"),
+    }
+}
+
+fn is_option(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(ref p) => match p.path.segments.last() {
+            Some(segment) => segment.ident == "Option",
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+pub fn impl_tag_renderable_attrs(ast: &syn::DeriveInput) -> TokenStream {
+    let mut pushes = Vec::new();
+    for field in get_named_fields(&get_ast_data(ast).fields).named.iter() {
+        let field_name = match field.ident {
+            Some(ref v) => v,
+            None => panic!("must have named field for renderable attrs derive gen"),
+        };
+
+        for attr in field.attrs.iter() {
+            let attr_name = attr.path.get_ident().unwrap().to_string();
+            match attr_name.as_str() {
+                // A single attribute, which is left off when it is an Option holding None
+                "tag_renderable_attr" => pushes.push(match is_option(&field.ty) {
+                    true => quote! {
+                        if let Some(ref a) = self.#field_name {
+                            attrs.push(a);
+                        }
+                    },
+                    false => quote! {
+                        attrs.push(&self.#field_name);
+                    },
+                }),
+                // A set of attributes, like the global attributes
+                "tag_renderable_global_attrs" => pushes.push(quote! {
+                    attrs.extend(htmldsl_internal::attr_traits::AttributeSet::attributes(&self.#field_name));
+                }),
+                // The style attribute, which is left off when there are no styles
+                "tag_renderable_style" => pushes.push(quote! {
+                    if !self.#field_name.values.is_empty() {
+                        attrs.push(&self.#field_name);
+                    }
+                }),
+                _ => (),
+            }
+        }
+    }
+
+    // Used in the quasi-quotation below as `#name`
+    let name = &ast.ident;
+
+    // Helper is provided for handling complex generic types correctly and effortlessly
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let ret = quote! {
+        impl #impl_generics htmldsl_internal::element_traits::TagRenderableAttrs for #name #ty_generics #where_clause {
+            fn get_attributes(&self) -> Vec<&dyn htmldsl_internal::attr_traits::Attribute> {
+                let mut attrs: Vec<&dyn htmldsl_internal::attr_traits::Attribute> = Vec::new();
+                #(#pushes)*
+                attrs
+            }
+        }
+    };
+    ret.into()
+}
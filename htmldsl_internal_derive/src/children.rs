@@ -1,6 +1,4 @@
 use proc_macro::TokenStream;
-use quote;
-use syn;
 
 use super::util;
 
@@ -25,12 +23,10 @@ enum ChildrenGenType {
 
 pub fn impl_tag_renderable_children(ast: &syn::DeriveInput) -> TokenStream {
     let mut o_gen_type = None;
-    for field in get_named_fields(&get_ast_data(&ast).fields).named.iter() {
+    for field in get_named_fields(&get_ast_data(ast).fields).named.iter() {
         for attr in field.attrs.iter() {
-            if o_gen_type.is_none() {
-                if attr.path.get_ident().unwrap().to_string()
-                    == "tag_renderable_children".to_string()
-                {
+            if *attr.path.get_ident().unwrap() == "tag_renderable_children" {
+                if o_gen_type.is_none() {
                     let metas: Vec<syn::NestedMeta> = match attr.parse_meta() {
                         Ok(syn::Meta::List(meta)) => meta.nested.into_iter().collect(),
                         _ => panic!("must supply list of 'key = \"value\"'"),
@@ -42,7 +38,7 @@ pub fn impl_tag_renderable_children(ast: &syn::DeriveInput) -> TokenStream {
 
                     let named_value = match metas[0] {
                         syn::NestedMeta::Meta(syn::Meta::NameValue(ref m)) => {
-                            if m.path.get_ident().unwrap().to_string() == "type".to_string() {
+                            if *m.path.get_ident().unwrap() == "type" {
                                 util::get_string_from_lit(&m.lit)
                             } else {
                                 panic!("must have 'tag_renderable_children(**type** = \"...\"'")
@@ -96,7 +92,7 @@ pub fn impl_tag_renderable_children(ast: &syn::DeriveInput) -> TokenStream {
 
     let ret = quote! {
         impl #impl_generics htmldsl_internal::element_traits::TagRenderableChildren for #name #ty_generics #where_clause {
-            fn get_children(&self) -> Result<Vec<Renderable>, String> {
+            fn get_children(&self) -> Result<Vec<Renderable>, htmldsl_internal::element_traits::RenderError> {
                 #body
             }
         }
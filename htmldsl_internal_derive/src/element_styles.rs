@@ -1,6 +1,4 @@
 use proc_macro::TokenStream;
-use quote;
-use syn;
 
 fn get_ast_data(ast: &syn::DeriveInput) -> &syn::DataStruct {
     match ast.data {
@@ -18,9 +16,9 @@ fn get_named_fields(fields: &syn::Fields) -> &syn::FieldsNamed {
 
 pub fn impl_tag_renderable_style_setter(ast: &syn::DeriveInput) -> TokenStream {
     let mut o_field_name = None;
-    for field in get_named_fields(&get_ast_data(&ast).fields).named.iter() {
+    for field in get_named_fields(&get_ast_data(ast).fields).named.iter() {
         for attr in field.attrs.iter() {
-            if attr.path.get_ident().unwrap().to_string() == "tag_renderable_style".to_string() {
+            if *attr.path.get_ident().unwrap() == "tag_renderable_style" {
                 if o_field_name.is_none() {
                     o_field_name = match field.ident {
                         Some(ref v) => Some(quote! { #v }),
@@ -43,14 +41,18 @@ pub fn impl_tag_renderable_style_setter(ast: &syn::DeriveInput) -> TokenStream {
     // Used in the quasi-quotation below as `#name`
     let name = &ast.ident;
 
+    // The element's own lifetime is the one its styles may borrow for
+    let lifetime = match ast.generics.lifetimes().next() {
+        Some(l) => &l.lifetime,
+        None => panic!("derive style setter requires a lifetime parameter, like `Div<'a>`"),
+    };
+
     // Helper is provided for handling complex generic types correctly and effortlessly
     let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
 
-    // TODO the `&'a dyn ...` should actually be the lifetime from the impl_generics, but I don't
-    // know how to extract that out.
     let ret = quote! {
-        impl #impl_generics htmldsl_internal::element_traits::TagRenderableStyleSetter#impl_generics for #name #ty_generics #where_clause {
-            fn add_style(mut self, styles: Vec<&'a dyn htmldsl_internal::style_traits::Style>) -> Self {
+        impl #impl_generics htmldsl_internal::element_traits::TagRenderableStyleSetter<#lifetime> for #name #ty_generics #where_clause {
+            fn add_style(mut self, styles: Vec<Box<dyn htmldsl_internal::style_traits::Style + #lifetime>>) -> Self {
                 self.#field_name.values = self.#field_name.values
                     .into_iter()
                     .chain(styles.into_iter())
@@ -0,0 +1,57 @@
+use proc_macro::TokenStream;
+
+fn get_ast_data(ast: &syn::DeriveInput) -> &syn::DataStruct {
+    match ast.data {
+        syn::Data::Struct(ref s) => s,
+        _ => panic!("derive global attrs setter only works on structs"),
+    }
+}
+
+fn get_named_fields(fields: &syn::Fields) -> &syn::FieldsNamed {
+    match fields {
+        syn::Fields::Named(ref n) => n,
+        _ => panic!("derive global attrs setter only works with named fields"),
+    }
+}
+
+pub fn impl_tag_renderable_global_attrs_setter(ast: &syn::DeriveInput) -> TokenStream {
+    let mut o_field_name = None;
+    for field in get_named_fields(&get_ast_data(ast).fields).named.iter() {
+        for attr in field.attrs.iter() {
+            if *attr.path.get_ident().unwrap() == "tag_renderable_global_attrs" {
+                if o_field_name.is_none() {
+                    o_field_name = match field.ident {
+                        Some(ref v) => Some((quote! { #v }, &field.ty)),
+                        None => {
+                            panic!("must have named field for global attrs setter derive gen")
+                        }
+                    };
+                } else {
+                    panic!("only one field may have the 'tag_renderable_global_attrs' attr");
+                }
+            }
+        }
+    }
+
+    let (field_name, field_type) = match o_field_name {
+        Some(s) => s,
+        None => panic!("must provide the renderable global attrs field"),
+    };
+
+    // Used in the quasi-quotation below as `#name`
+    let name = &ast.ident;
+
+    // Helper is provided for handling complex generic types correctly and effortlessly
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    let ret = quote! {
+        impl #impl_generics htmldsl_internal::element_traits::TagRenderableGlobalAttrs for #name #ty_generics #where_clause {
+            type GlobalAttrs = #field_type;
+
+            fn global_attrs_mut(&mut self) -> &mut #field_type {
+                &mut self.#field_name
+            }
+        }
+    };
+    ret.into()
+}
@@ -1,17 +1,21 @@
 use proc_macro::TokenStream;
-use quote;
-use syn;
 
 pub fn impl_tag_renderable_into_element(ast: &syn::DeriveInput) -> TokenStream {
     // Used in the quasi-quotation below as `#name`
     let name = &ast.ident;
 
+    // The element's own lifetime is the one the resulting `Element` is generic over
+    let lifetime = match ast.generics.lifetimes().next() {
+        Some(l) => &l.lifetime,
+        None => panic!("derive into element requires a lifetime parameter, like `Div<'a>`"),
+    };
+
     // Helper is provided for handling complex generic types correctly and effortlessly
-    let (_, _, where_clause) = ast.generics.split_for_impl();
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
 
     let ret = quote! {
-        impl htmldsl_internal::element_traits::TagRenderableIntoElement for #name <'static> #where_clause {
-            fn into_element(self) -> htmldsl_internal::element_traits::Element {
+        impl #impl_generics htmldsl_internal::element_traits::TagRenderableIntoElement<#lifetime> for #name #ty_generics #where_clause {
+            fn into_element(self) -> htmldsl_internal::element_traits::Element<#lifetime> {
                 htmldsl_internal::element_traits::Element::Tag(
                     Box::new(
                         self
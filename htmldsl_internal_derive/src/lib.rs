@@ -5,9 +5,11 @@ extern crate syn;
 #[macro_use]
 extern crate quote;
 
+mod attrs;
 mod children;
 mod element_styles;
 mod generic;
+mod global_attrs;
 mod into_element;
 mod name;
 mod tag;
@@ -43,6 +45,26 @@ pub fn derive_tag_renderable_children(input: TokenStream) -> TokenStream {
     children::impl_tag_renderable_children(&input)
 }
 
+#[proc_macro_derive(
+    TagRenderableAttrs,
+    attributes(tag_renderable_attr, tag_renderable_global_attrs, tag_renderable_style)
+)]
+pub fn derive_tag_renderable_attrs(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+
+    attrs::impl_tag_renderable_attrs(&input)
+}
+
+#[proc_macro_derive(
+    TagRenderableGlobalAttrsSetter,
+    attributes(tag_renderable_global_attrs)
+)]
+pub fn derive_tag_renderable_global_attrs_setter(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+
+    global_attrs::impl_tag_renderable_global_attrs_setter(&input)
+}
+
 #[proc_macro_derive(TagRenderableStyleSetter, attributes(tag_renderable_style))]
 pub fn derive_tag_renderable_style_setter(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as syn::DeriveInput);
@@ -1,23 +1,29 @@
 use proc_macro::TokenStream;
-use quote;
-use syn;
 
 use super::util;
 
 pub fn impl_tag_renderable_name(ast: &syn::DeriveInput) -> TokenStream {
-    let tag_renderable_name_usage = "#[tag_renderable_name(name = \"html_element_name\")]";
+    let tag_renderable_name_usage =
+        "#[tag_renderable_name(name = \"html_element_name\")] or #[tag_renderable_name(name = \"html_element_name\", void)]";
 
     let mut o_name = None;
+    let mut void = false;
     for meta_items in ast.attrs.iter().filter_map(get_renderable_name_meta_items) {
         for meta_item in meta_items {
             match meta_item {
                 // Parse `#[tag_renderable_name(name = "foo")]`
                 syn::NestedMeta::Meta(syn::Meta::NameValue(ref m))
-                    if m.path.get_ident().unwrap().to_string() == "name" =>
+                    if *m.path.get_ident().unwrap() == "name" =>
                 {
                     let s = util::get_string_from_lit(&m.lit);
                     o_name = Some(s);
                 }
+                // Parse `#[tag_renderable_name(..., void)]`
+                syn::NestedMeta::Meta(syn::Meta::Path(ref p))
+                    if *p.get_ident().unwrap() == "void" =>
+                {
+                    void = true;
+                }
                 _ => panic!(
                     "incorrect usage of custom attribute, use: {}",
                     tag_renderable_name_usage
@@ -40,18 +46,39 @@ pub fn impl_tag_renderable_name(ast: &syn::DeriveInput) -> TokenStream {
     // Helper is provided for handling complex generic types correctly and effortlessly
     let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
 
-    let ret = quote! {
-        impl #impl_generics htmldsl_internal::element_traits::TagRenderableName for #name #ty_generics #where_clause {
-            fn get_name(&self) -> String {
-                return #renderable_name.to_string();
+    let ret = match void {
+        // Void elements also get their (empty) children generated here, so that they can never
+        // be given children of their own
+        true => quote! {
+            impl #impl_generics htmldsl_internal::element_traits::TagRenderableName for #name #ty_generics #where_clause {
+                fn get_name(&self) -> String {
+                    return #renderable_name.to_string();
+                }
+
+                fn is_void(&self) -> bool {
+                    true
+                }
             }
-        }
+
+            impl #impl_generics htmldsl_internal::element_traits::TagRenderableChildren for #name #ty_generics #where_clause {
+                fn get_children(&self) -> Result<Vec<htmldsl_internal::element_traits::Renderable<'_>>, htmldsl_internal::element_traits::RenderError> {
+                    Ok(Vec::new())
+                }
+            }
+        },
+        false => quote! {
+            impl #impl_generics htmldsl_internal::element_traits::TagRenderableName for #name #ty_generics #where_clause {
+                fn get_name(&self) -> String {
+                    return #renderable_name.to_string();
+                }
+            }
+        },
     };
     ret.into()
 }
 
 fn get_renderable_name_meta_items(attr: &syn::Attribute) -> Option<Vec<syn::NestedMeta>> {
-    if attr.path.get_ident().unwrap().to_string() != "tag_renderable_name" {
+    if *attr.path.get_ident().unwrap() != "tag_renderable_name" {
         return Some(Vec::new());
     }
 
@@ -1,6 +1,4 @@
 use proc_macro::TokenStream;
-use quote;
-use syn;
 
 pub fn impl_tag_renderable(ast: &syn::DeriveInput) -> TokenStream {
     // Used in the quasi-quotation below as `#name`
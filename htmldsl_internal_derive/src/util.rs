@@ -1,5 +1,3 @@
-use syn;
-
 pub fn get_string_from_lit(lit: &syn::Lit) -> String {
     if let syn::Lit::Str(ref s) = *lit {
         s.value()